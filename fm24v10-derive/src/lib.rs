@@ -0,0 +1,148 @@
+//! Derive macro for memory-mapped F-RAM layouts, re-exported by the `fm24v10`
+//! crate with the `derive` feature. See `fm24v10::FramLayout`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Expr, Fields, Path, parse_macro_input};
+
+/// Derives `fm24v10::FramLayout` and a `Field<T>` handle per field.
+///
+/// The handles are associated constants named after the fields in upper case.
+/// Supported container attributes:
+/// * `#[fram(offset = <expr>)]`: address of the struct in the F-RAM, 0 by default.
+/// * `#[fram(variant = <path>)]`: variant whose capacity the layout must fit,
+///   `fm24v10::variant::Fm24v10` by default.
+///
+/// # Errors
+///
+/// Structs without `#[repr(C)]` are rejected, as their layout may change:
+///
+/// ```compile_fail
+/// #[derive(fm24v10::FramLayout)]
+/// struct Config {
+///     boot_count: u32,
+/// }
+/// ```
+///
+/// A layout exceeding the capacity of the variant fails const evaluation:
+///
+/// ```compile_fail
+/// #[derive(fm24v10::FramLayout)]
+/// #[fram(offset = 0x7F0, variant = fm24v10::variant::Fm24cl16b)]
+/// #[repr(C)]
+/// struct Config {
+///     serial: [u8; 32],
+/// }
+/// ```
+#[proc_macro_derive(FramLayout, attributes(fram))]
+pub fn derive_fram_layout(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "FramLayout can't be derived for generic structs",
+        ));
+    }
+    if !has_repr_c(&input)? {
+        return Err(Error::new(
+            Span::call_site(),
+            "FramLayout requires #[repr(C)] for a stable field layout",
+        ));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            Span::call_site(),
+            "FramLayout can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            &data.fields,
+            "FramLayout requires named fields",
+        ));
+    };
+
+    let mut offset: Expr = syn::parse_quote!(0);
+    let mut variant: Path = syn::parse_quote!(::fm24v10::variant::Fm24v10);
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("fram"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("offset") {
+                offset = meta.value()?.parse()?;
+            } else if meta.path.is_ident("variant") {
+                variant = meta.value()?.parse()?;
+            } else {
+                return Err(meta.error("expected `offset` or `variant`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let handles = fields.named.iter().map(|field| {
+        let ident = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        let unraw = ident.to_string();
+        let unraw = unraw.trim_start_matches("r#");
+        let handle = format_ident!("{}", unraw.to_uppercase());
+        let doc = format!("Handle of the `{unraw}` field.");
+        quote! {
+            #[doc = #doc]
+            pub const #handle: ::fm24v10::Field<#ty> = ::fm24v10::Field::new(
+                <Self as ::fm24v10::FramLayout>::OFFSET
+                    + ::core::mem::offset_of!(Self, #ident) as u32,
+                ::fm24v10::Endian::Little,
+            );
+        }
+    });
+
+    Ok(quote! {
+        impl ::fm24v10::FramLayout for #name {
+            const OFFSET: u32 = #offset;
+            const BYTES: usize = ::core::mem::size_of::<Self>();
+        }
+
+        impl #name {
+            #(#handles)*
+        }
+
+        const _: () = ::core::assert!(
+            <#name as ::fm24v10::FramLayout>::OFFSET as usize
+                + <#name as ::fm24v10::FramLayout>::BYTES
+                <= <#variant as ::fm24v10::variant::Variant>::CAPACITY_BYTES,
+            "layout doesn't fit the F-RAM capacity"
+        );
+    })
+}
+
+/// Returns `true` if the struct has a `#[repr(C)]` attribute.
+fn has_repr_c(input: &DeriveInput) -> syn::Result<bool> {
+    let mut repr_c = false;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                repr_c = true;
+            } else if meta.input.peek(syn::token::Paren) {
+                // e.g. packed(2) or align(4)
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        })?;
+    }
+    Ok(repr_c)
+}
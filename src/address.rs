@@ -0,0 +1,82 @@
+//! Slave and memory address construction shared by the drivers.
+
+use embedded_hal::i2c::Error as I2cError;
+
+use crate::Error;
+use crate::variant::Variant;
+
+/// Represents the hardware address selection pins A0, A1 and A2.
+/// The fields are expected to be 0 or 1, corresponding to the pin states.
+/// Pins a variant doesn't have, or uses for page select, are ignored.
+/// Construct it by field name, e.g. `Address { a1: 1, ..Default::default() }`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Address {
+    /// A0 pin state.
+    pub a0: u8,
+    /// A1 pin state.
+    pub a1: u8,
+    /// A2 pin state.
+    pub a2: u8,
+}
+
+impl Address {
+    /// Pin part of the 7-bit I2C address: 0b0000_A2A1A0
+    const fn pin_bits(self) -> u8 {
+        ((self.a2 & 0x01) << 2) | ((self.a1 & 0x01) << 1) | (self.a0 & 0x01)
+    }
+}
+
+/// Device Type code for the I2C F-RAM family (4-bit prefix: 1010b).
+const DEVICE_TYPE_CODE: u8 = 0b1010;
+
+/// Largest number of memory address bytes sent by any variant.
+pub(crate) const MAX_MEMORY_ADDRESS_BYTES: usize = 2;
+
+/// Base I2C address part (0b1010_A2A1A0), derived from device type and the
+/// address pins the variant has.
+pub(crate) fn base_address<V: Variant>(address_pins: Address) -> u8 {
+    (DEVICE_TYPE_CODE << 3) | (address_pins.pin_bits() & V::ADDRESS_PINS)
+}
+
+/// Constructs the final 7-bit I2C device address for a given memory operation.
+/// It combines the base address (derived from device type and address pins)
+/// with the page select bits (memory address bits above the address bytes).
+pub(crate) fn slave_address<V: Variant>(base_address: u8, memory_offset: u32) -> u8 {
+    // Page select bits occupy the LSBs of the slave address, which are 0 in
+    // base_address for variants that use them.
+    let page_select_mask = (1u32 << V::PAGE_SELECT_BITS) - 1;
+    let page_select_bits = (memory_offset >> (8 * V::MEMORY_ADDRESS_BYTES)) & page_select_mask;
+
+    base_address | (page_select_bits as u8)
+}
+
+/// Checks that `len` bytes starting at `offset` fit into the device.
+pub(crate) fn check_bounds<V: Variant, E: I2cError>(
+    offset: u32,
+    len: usize,
+) -> Result<(), Error<E>> {
+    if offset >= V::CAPACITY_BYTES as u32 || len > (V::CAPACITY_BYTES - offset as usize) {
+        return Err(Error::OutOfBounds);
+    }
+    Ok(())
+}
+
+/// Memory address bytes sent after the slave address, most significant first.
+pub(crate) struct MemoryAddress {
+    bytes: [u8; MAX_MEMORY_ADDRESS_BYTES],
+    len: usize,
+}
+
+impl MemoryAddress {
+    pub(crate) fn new<V: Variant>(offset: u32) -> Self {
+        Self {
+            bytes: [((offset >> 8) & 0xFF) as u8, (offset & 0xFF) as u8],
+            len: V::MEMORY_ADDRESS_BYTES,
+        }
+    }
+
+    /// The last `V::MEMORY_ADDRESS_BYTES` bytes, the ones actually sent.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.bytes[MAX_MEMORY_ADDRESS_BYTES - self.len..]
+    }
+}
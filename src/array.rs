@@ -0,0 +1,111 @@
+//! Several F-RAM chips exposed as one linear address space.
+//!
+//! With different address pin settings, several chips can share one I2C bus,
+//! e.g. up to four FM24V10s for 512KB. Each chip is driven by its own
+//! [`Fm24v10`], so every driver needs its own handle to the shared bus, e.g.
+//! the async I2C devices of `embassy-embedded-hal`. `embedded-hal-bus` only
+//! shares blocking I2C buses.
+
+use core::fmt::Debug;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+
+use crate::variant::Variant;
+use crate::{Error, Fm24v10};
+
+/// `N` equally sized F-RAM chips concatenated in order.
+///
+/// Chip `i` holds the addresses `i * V::CAPACITY_BYTES` up to
+/// `(i + 1) * V::CAPACITY_BYTES - 1`. Operations crossing a chip boundary are
+/// split transparently.
+pub struct Fm24v10Array<I2C, V, WP, const N: usize> {
+    devices: [Fm24v10<I2C, V, WP>; N],
+}
+
+impl<I2C, V, WP, E, const N: usize> Fm24v10Array<I2C, V, WP, N>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    /// Creates an array from the drivers of its chips, lowest addresses first.
+    pub fn new(devices: [Fm24v10<I2C, V, WP>; N]) -> Self {
+        Self { devices }
+    }
+
+    /// Releases the drivers of the chips.
+    pub fn into_inner(self) -> [Fm24v10<I2C, V, WP>; N] {
+        self.devices
+    }
+
+    /// The driver of chip `index`, e.g. to put it to sleep.
+    pub fn device_mut(&mut self, index: usize) -> Option<&mut Fm24v10<I2C, V, WP>> {
+        self.devices.get_mut(index)
+    }
+
+    /// Checks that `len` bytes starting at `offset` fit into the array.
+    fn check_bounds(offset: u32, len: usize) -> Result<(), Error<E>> {
+        let capacity = N * V::CAPACITY_BYTES;
+        if offset as usize >= capacity || len > capacity - offset as usize {
+            return Err(Error::OutOfBounds);
+        }
+        Ok(())
+    }
+
+    /// Splits an array offset into chip index, chip offset and the number of
+    /// bytes left on that chip.
+    fn locate(offset: u32) -> (usize, u32, usize) {
+        let index = offset as usize / V::CAPACITY_BYTES;
+        let chip_offset = offset as usize % V::CAPACITY_BYTES;
+        (index, chip_offset as u32, V::CAPACITY_BYTES - chip_offset)
+    }
+
+    /// Read a slice of data from the array.
+    ///
+    /// # Arguments
+    /// * `offset`: The starting offset to read from (0 to N * CAPACITY_BYTES - 1).
+    /// * `bytes`: A mutable slice to store the read data.
+    pub async fn read(&mut self, mut offset: u32, mut bytes: &mut [u8]) -> Result<(), Error<E>> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        Self::check_bounds(offset, bytes.len())?;
+
+        while !bytes.is_empty() {
+            let (index, chip_offset, left) = Self::locate(offset);
+            let (chunk, rest) = bytes.split_at_mut(bytes.len().min(left));
+            self.devices[index].read(chip_offset, chunk).await?;
+            offset += chunk.len() as u32;
+            bytes = rest;
+        }
+        Ok(())
+    }
+
+    /// Get the total capacity of the array in bytes.
+    pub async fn capacity(&self) -> Result<usize, Error<E>> {
+        Ok(N * V::CAPACITY_BYTES)
+    }
+
+    /// Write a slice of data to the array.
+    ///
+    /// # Arguments
+    /// * `offset`: The starting offset to write to (0 to N * CAPACITY_BYTES - 1).
+    /// * `data`: The slice of data to write.
+    pub async fn write(&mut self, mut offset: u32, mut data: &[u8]) -> Result<(), Error<E>> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        Self::check_bounds(offset, data.len())?;
+
+        while !data.is_empty() {
+            let (index, chip_offset, left) = Self::locate(offset);
+            let (chunk, rest) = data.split_at(data.len().min(left));
+            self.devices[index].write(chip_offset, chunk).await?;
+            offset += chunk.len() as u32;
+            data = rest;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+//! Command line tool for FM24V10 F-RAMs on Linux i2c-dev buses.
+//!
+//! ```text
+//! fm24v10 [--bus <path>] [--pins <A2A1A0>] <command>
+//!
+//! Commands:
+//!   id                       Print the Device ID and check it
+//!   dump <file>              Save the whole array to an image file
+//!   flash <file>             Write an image file and verify it
+//!   hexdump <offset> <len>   Print a range as hex and ASCII
+//!   verify <file>            Compare the array against an image file
+//! ```
+//!
+//! `--bus` defaults to `/dev/i2c-1`, `--pins` to `0`. Numbers can be given in
+//! decimal or with a `0x` prefix.
+
+use std::fmt::Debug;
+use std::process::ExitCode;
+use std::{env, fs};
+
+use fm24v10::Address;
+use fm24v10::blocking::Fm24v10;
+use linux_embedded_hal::I2cdev;
+
+/// Number of bytes read or written per driver call.
+const CHUNK_BYTES: usize = 256;
+
+/// Number of differing ranges listed by `verify`.
+const MAX_REPORTED_DIFFS: usize = 16;
+
+const USAGE: &str = "usage: fm24v10 [--bus <path>] [--pins <A2A1A0>] <command>
+
+commands:
+  id                       print the Device ID and check it
+  dump <file>              save the whole array to an image file
+  flash <file>             write an image file and verify it
+  hexdump <offset> <len>   print a range as hex and ASCII
+  verify <file>            compare the array against an image file";
+
+type Result<T> = std::result::Result<T, String>;
+
+fn main() -> ExitCode {
+    match run(env::args().skip(1).collect()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Runs the command line, returns `false` if verification failed.
+fn run(mut args: Vec<String>) -> Result<bool> {
+    let bus = take_option(&mut args, "--bus")?.unwrap_or_else(|| "/dev/i2c-1".into());
+    let pins = match take_option(&mut args, "--pins")? {
+        Some(pins) => parse_number(&pins)?,
+        None => 0,
+    };
+    if pins > 0b111 {
+        return Err(format!("invalid pins {pins:#05b}, expected A2A1A0 bits"));
+    }
+
+    let (command, args) = args.split_first().ok_or_else(|| USAGE.to_string())?;
+    let i2c = I2cdev::new(&bus).map_err(|e| format!("{bus}: {e}"))?;
+    let address = Address {
+        a0: pins as u8 & 1,
+        a1: (pins >> 1) as u8 & 1,
+        a2: (pins >> 2) as u8 & 1,
+    };
+    let mut fram = Fm24v10::new(i2c, address);
+
+    match (command.as_str(), args) {
+        ("id", []) => id(&mut fram),
+        ("dump", [file]) => dump(&mut fram, file),
+        ("flash", [file]) => flash(&mut fram, file),
+        ("hexdump", [offset, len]) => hexdump(&mut fram, parse_number(offset)?, parse_number(len)?),
+        ("verify", [file]) => verify(&mut fram, file),
+        _ => Err(USAGE.into()),
+    }
+}
+
+/// Removes `name` and its value from `args`.
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>> {
+    let Some(index) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(format!("{name} requires a value"));
+    }
+    let value = args.remove(index + 1);
+    args.remove(index);
+    Ok(Some(value))
+}
+
+fn parse_number(s: &str) -> Result<u32> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+    .map_err(|_| format!("invalid number {s:?}"))
+}
+
+fn i2c_error(e: impl Debug) -> String {
+    format!("F-RAM access failed: {e:?}")
+}
+
+fn id(fram: &mut Fm24v10<I2cdev>) -> Result<bool> {
+    let id = fram.device_id().map_err(i2c_error)?;
+    println!(
+        "manufacturer {:#05x}, product {:#05x}, density {:#x}",
+        id.manufacturer,
+        id.product,
+        id.density()
+    );
+    match fram.verify() {
+        Ok(()) => {
+            println!("FM24V10 detected");
+            Ok(true)
+        }
+        Err(fm24v10::Error::DeviceIdMismatch(_)) => {
+            println!("not an FM24V10");
+            Ok(false)
+        }
+        Err(e) => Err(i2c_error(e)),
+    }
+}
+
+/// Reads `len` bytes starting at `offset` in chunks.
+fn read_range(fram: &mut Fm24v10<I2cdev>, offset: u32, len: usize) -> Result<Vec<u8>> {
+    let mut data = vec![0; len];
+    for (i, chunk) in data.chunks_mut(CHUNK_BYTES).enumerate() {
+        fram.read(offset + (i * CHUNK_BYTES) as u32, chunk)
+            .map_err(i2c_error)?;
+    }
+    Ok(data)
+}
+
+/// Reads an image file, which must match the capacity.
+fn read_image(fram: &Fm24v10<I2cdev>, file: &str) -> Result<Vec<u8>> {
+    let image = fs::read(file).map_err(|e| format!("{file}: {e}"))?;
+    let capacity = fram.capacity().map_err(i2c_error)?;
+    if image.len() != capacity {
+        return Err(format!(
+            "{file}: image is {} bytes, the device has {capacity}",
+            image.len()
+        ));
+    }
+    Ok(image)
+}
+
+fn dump(fram: &mut Fm24v10<I2cdev>, file: &str) -> Result<bool> {
+    let capacity = fram.capacity().map_err(i2c_error)?;
+    let data = read_range(fram, 0, capacity)?;
+    fs::write(file, data).map_err(|e| format!("{file}: {e}"))?;
+    println!("{capacity} bytes written to {file}");
+    Ok(true)
+}
+
+fn flash(fram: &mut Fm24v10<I2cdev>, file: &str) -> Result<bool> {
+    let image = read_image(fram, file)?;
+    for (i, chunk) in image.chunks(CHUNK_BYTES).enumerate() {
+        fram.write((i * CHUNK_BYTES) as u32, chunk)
+            .map_err(i2c_error)?;
+    }
+    println!("{} bytes flashed from {file}", image.len());
+    compare(fram, &image)
+}
+
+fn verify(fram: &mut Fm24v10<I2cdev>, file: &str) -> Result<bool> {
+    let image = read_image(fram, file)?;
+    compare(fram, &image)
+}
+
+/// Reads the array back and lists ranges differing from `image`.
+fn compare(fram: &mut Fm24v10<I2cdev>, image: &[u8]) -> Result<bool> {
+    let data = read_range(fram, 0, image.len())?;
+    let mut diffs = Vec::new();
+    let mut start = None;
+    for (offset, (a, b)) in data.iter().zip(image).enumerate() {
+        match (a != b, start) {
+            (true, None) => start = Some(offset),
+            (false, Some(first)) => {
+                diffs.push(first..offset);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(first) = start {
+        diffs.push(first..data.len());
+    }
+
+    if diffs.is_empty() {
+        println!("contents match");
+        return Ok(true);
+    }
+    let bytes: usize = diffs.iter().map(|range| range.len()).sum();
+    println!("{bytes} bytes differ in {} ranges", diffs.len());
+    for range in diffs.iter().take(MAX_REPORTED_DIFFS) {
+        println!("  {:#07x}..{:#07x}", range.start, range.end);
+    }
+    if diffs.len() > MAX_REPORTED_DIFFS {
+        println!("  ...");
+    }
+    Ok(false)
+}
+
+fn hexdump(fram: &mut Fm24v10<I2cdev>, offset: u32, len: u32) -> Result<bool> {
+    let capacity = fram.capacity().map_err(i2c_error)?;
+    if offset as usize + len as usize > capacity {
+        return Err(format!("range exceeds the capacity of {capacity} bytes"));
+    }
+    let data = read_range(fram, offset, len as usize)?;
+    for (i, line) in data.chunks(16).enumerate() {
+        let hex: Vec<String> = line.iter().map(|b| format!("{b:02x}")).collect();
+        let ascii: String = line
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        println!(
+            "{:05x}  {:<47}  |{ascii}|",
+            offset as usize + i * 16,
+            hex.join(" ")
+        );
+    }
+    Ok(true)
+}
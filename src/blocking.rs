@@ -0,0 +1,283 @@
+//! Blocking driver built on the embedded-hal 1.0 [`I2c`] trait.
+//!
+//! For code running without an executor, e.g. bootloaders and panic handlers.
+//! Offers the core `read`/`write`/`capacity`, Device ID and software write
+//! protection API of the async driver along with the [`embedded_storage`]
+//! blocking trait implementations. The write-protect pin and sleep mode aren't
+//! supported.
+
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use embedded_hal::i2c::{Error as I2cError, I2c};
+use embedded_storage::nor_flash::{ErrorType, MultiwriteNorFlash, NorFlash, ReadNorFlash};
+use embedded_storage::{ReadStorage, Storage};
+
+use crate::address::{
+    MAX_MEMORY_ADDRESS_BYTES, MemoryAddress, base_address, check_bounds, slave_address,
+};
+use crate::device_id::{
+    DEVICE_ID_BYTES, RESERVED_SLAVE_ID, check_device_id, device_id_request, expected_device_id,
+};
+use crate::erase::{ERASED, erase_chunks};
+use crate::protect::ProtectedRegions;
+use crate::variant::{self, Variant};
+use crate::{Address, DeviceId, Error, ProvisioningToken};
+
+/// Number of data bytes sent per I2C write by [`Fm24v10::write`].
+pub const WRITE_CHUNK_BYTES: usize = 64;
+
+/// Blocking driver for the FM24V10 and related I2C F-RAMs
+pub struct Fm24v10<I2C, V = variant::Fm24v10> {
+    i2c: I2C,
+    /// Base I2C address part (0b1010_A2A1A0), see [`crate::Fm24v10`].
+    base_address: u8,
+    /// Address ranges that [`Fm24v10::write`] refuses to touch.
+    protected: ProtectedRegions,
+    _variant: PhantomData<V>,
+}
+
+impl<I2C, E> Fm24v10<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: I2cError,
+{
+    /// Creates a new blocking FM24V10 driver instance.
+    ///
+    /// # Arguments
+    /// * `i2c`: The I2C bus peripheral.
+    /// * `address_pins`: The state of the A2 and A1 hardware address pins.
+    pub fn new(i2c: I2C, address_pins: Address) -> Self {
+        Self::new_variant(i2c, variant::Fm24v10, address_pins)
+    }
+}
+
+impl<I2C, V, E> Fm24v10<I2C, V>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    E: I2cError,
+{
+    /// Creates a new blocking driver instance for another member of the F-RAM family.
+    ///
+    /// # Arguments
+    /// * `i2c`: The I2C bus peripheral.
+    /// * `_variant`: The device variant, e.g. `variant::Fm24cl16b`.
+    /// * `address_pins`: The state of the hardware address pins. Pins the
+    ///   variant doesn't have are ignored.
+    pub fn new_variant(i2c: I2C, _variant: V, address_pins: Address) -> Self {
+        Self {
+            i2c,
+            base_address: base_address::<V>(address_pins),
+            protected: ProtectedRegions::default(),
+            _variant: PhantomData,
+        }
+    }
+
+    /// Read a slice of data from the F-RAM.
+    ///
+    /// # Arguments
+    /// * `offset`: The starting memory address offset to read from (0 to CAPACITY_BYTES - 1).
+    /// * `bytes`: A mutable slice to store the read data.
+    pub fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Error<E>> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        check_bounds::<V, E>(offset, bytes.len())?;
+
+        let address = slave_address::<V>(self.base_address, offset);
+        let mem_addr_payload = MemoryAddress::new::<V>(offset);
+
+        self.i2c
+            .write_read(address, mem_addr_payload.as_bytes(), bytes)
+            .map_err(Error::I2c)
+    }
+
+    /// Read the 3-byte Device ID, see [`crate::Fm24v10::device_id`].
+    pub fn device_id(&mut self) -> Result<DeviceId, Error<E>> {
+        let mut id = [0u8; DEVICE_ID_BYTES];
+        self.i2c
+            .write_read(
+                RESERVED_SLAVE_ID,
+                &device_id_request(self.base_address),
+                &mut id,
+            )
+            .map_err(Error::I2c)?;
+        Ok(DeviceId::from_bytes(id))
+    }
+
+    /// Check that the connected part is the expected variant, see
+    /// [`crate::Fm24v10::verify`].
+    pub fn verify(&mut self) -> Result<(), Error<E>> {
+        let expected = expected_device_id::<V, E>()?;
+        check_device_id(expected, self.device_id()?)
+    }
+
+    /// Destroys the driver and returns the I2C bus.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Get the total capacity of the F-RAM in bytes.
+    pub fn capacity(&self) -> Result<usize, Error<E>> {
+        Ok(V::CAPACITY_BYTES)
+    }
+
+    /// Mark an address range as read-only in software, see
+    /// [`crate::Fm24v10::protect`].
+    ///
+    /// Protected regions are per driver instance. A bootloader or panic handler
+    /// using this driver has to protect the ranges itself.
+    pub fn protect(&mut self, range: Range<u32>) -> Result<(), Error<E>> {
+        self.protected.add::<V, E>(range)
+    }
+
+    /// Remove all software write-protected regions.
+    pub fn clear_protected_regions(&mut self, _token: &ProvisioningToken) {
+        self.protected.clear();
+    }
+
+    /// Write a slice of data to the F-RAM.
+    ///
+    /// Writes overlapping a protected region fail with [`Error::WriteProtected`].
+    ///
+    /// The data is sent in chunks of up to [`WRITE_CHUNK_BYTES`], each copied
+    /// behind its memory address into a stack buffer and sent with a single
+    /// [`I2c::write`]. Some HALs, e.g. `linux-embedded-hal`, put a repeated
+    /// start between the write operations of a transaction, which the device
+    /// would take as a new memory address.
+    ///
+    /// # Arguments
+    /// * `offset`: The starting memory address offset to write to (0 to CAPACITY_BYTES - 1).
+    /// * `data`: The slice of data to write.
+    pub fn write(&mut self, offset: u32, data: &[u8]) -> Result<(), Error<E>> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.protected.check_writable::<V, E>(offset, data.len())?;
+
+        self.write_unchecked(offset, data)
+    }
+
+    /// Write a slice of data to the F-RAM, ignoring software write protection,
+    /// see [`crate::Fm24v10::write_provisioning`].
+    ///
+    /// # Arguments
+    /// * `_token`: Proof that bypassing the protection is intended.
+    /// * `offset`: The starting memory address offset to write to (0 to CAPACITY_BYTES - 1).
+    /// * `data`: The slice of data to write.
+    pub fn write_provisioning(
+        &mut self,
+        _token: &ProvisioningToken,
+        offset: u32,
+        data: &[u8],
+    ) -> Result<(), Error<E>> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        check_bounds::<V, E>(offset, data.len())?;
+
+        self.write_unchecked(offset, data)
+    }
+
+    /// Performs a bounds-checked write.
+    fn write_unchecked(&mut self, mut offset: u32, data: &[u8]) -> Result<(), Error<E>> {
+        let mut buf = [0u8; MAX_MEMORY_ADDRESS_BYTES + WRITE_CHUNK_BYTES];
+        for chunk in data.chunks(WRITE_CHUNK_BYTES) {
+            let i2c_7bit_address = slave_address::<V>(self.base_address, offset);
+            let mem_addr_payload = MemoryAddress::new::<V>(offset);
+            let address_len = mem_addr_payload.as_bytes().len();
+            buf[..address_len].copy_from_slice(mem_addr_payload.as_bytes());
+            buf[address_len..address_len + chunk.len()].copy_from_slice(chunk);
+
+            self.i2c
+                .write(i2c_7bit_address, &buf[..address_len + chunk.len()])
+                .map_err(Error::I2c)?;
+            offset += chunk.len() as u32;
+        }
+        Ok(())
+    }
+}
+
+impl<I2C, V, E> ErrorType for Fm24v10<I2C, V>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    E: I2cError,
+{
+    type Error = Error<E>;
+}
+
+impl<I2C, V, E> ReadNorFlash for Fm24v10<I2C, V>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    E: I2cError,
+{
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        Fm24v10::read(self, offset, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        V::CAPACITY_BYTES
+    }
+}
+
+impl<I2C, V, E> NorFlash for Fm24v10<I2C, V>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    E: I2cError,
+{
+    const WRITE_SIZE: usize = 1;
+    const ERASE_SIZE: usize = 1;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        for (offset, len) in erase_chunks(from, to, V::CAPACITY_BYTES)? {
+            Fm24v10::write(self, offset, &ERASED[..len])?;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        Fm24v10::write(self, offset, bytes)
+    }
+}
+
+impl<I2C, V, E> MultiwriteNorFlash for Fm24v10<I2C, V>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    E: I2cError,
+{
+}
+
+impl<I2C, V, E> ReadStorage for Fm24v10<I2C, V>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    E: I2cError,
+{
+    type Error = Error<E>;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        Fm24v10::read(self, offset, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        V::CAPACITY_BYTES
+    }
+}
+
+impl<I2C, V, E> Storage for Fm24v10<I2C, V>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    E: I2cError,
+{
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        Fm24v10::write(self, offset, bytes)
+    }
+}
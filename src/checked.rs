@@ -0,0 +1,74 @@
+//! CRC-protected reads and writes.
+//!
+//! The data is followed by a checksum trailer of [`Checksum::BYTES`] bytes,
+//! [`Crc16`](crate::Crc16) with the `crc16` feature or [`Crc32`](crate::Crc32)
+//! with the `crc32` feature. Data has to be read back with the checksum it was
+//! written with.
+
+use core::fmt::Debug;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+
+use crate::address::check_bounds;
+use crate::checksum::{Checksum, MAX_CHECKSUM_BYTES};
+use crate::variant::Variant;
+use crate::{Error, Fm24v10};
+
+impl<I2C, V, WP, E> Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    /// Write a slice of data followed by its checksum.
+    ///
+    /// Occupies `data.len()` + [`Checksum::BYTES`] bytes starting at `offset`,
+    /// written in a single transaction. Nothing is written if any of them is
+    /// out of bounds or write-protected.
+    ///
+    /// # Arguments
+    /// * `offset`: The starting memory address offset to write to.
+    /// * `data`: The slice of data to write.
+    /// * `_checksum`: The checksum algorithm, e.g. [`Crc32`](crate::Crc32).
+    pub async fn write_checked<C: Checksum>(
+        &mut self,
+        offset: u32,
+        data: &[u8],
+        _checksum: C,
+    ) -> Result<(), Error<E>> {
+        let mut trailer = [0u8; MAX_CHECKSUM_BYTES];
+        C::trailer(data, &mut trailer);
+
+        self.write_vectored(offset, &[data, &trailer[..C::BYTES]])
+            .await
+    }
+
+    /// Read a slice of data written by [`Fm24v10::write_checked`] and verify its checksum.
+    ///
+    /// Returns [`Error::ChecksumMismatch`] if the data doesn't match the trailer.
+    ///
+    /// # Arguments
+    /// * `offset`: The starting memory address offset to read from.
+    /// * `bytes`: A mutable slice to store the read data, of the length written.
+    /// * `_checksum`: The checksum algorithm the data was written with.
+    pub async fn read_checked<C: Checksum>(
+        &mut self,
+        offset: u32,
+        bytes: &mut [u8],
+        _checksum: C,
+    ) -> Result<(), Error<E>> {
+        check_bounds::<V, E>(offset, bytes.len() + C::BYTES)?;
+
+        let mut stored = [0u8; MAX_CHECKSUM_BYTES];
+        let mut expected = [0u8; MAX_CHECKSUM_BYTES];
+        self.read_vectored(offset, &mut [bytes, &mut stored[..C::BYTES]])
+            .await?;
+        C::trailer(bytes, &mut expected);
+        if expected[..C::BYTES] != stored[..C::BYTES] {
+            return Err(Error::ChecksumMismatch);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+//! CRC checksums used by the framed storage formats.
+
+use core::fmt::Debug;
+
+#[cfg(feature = "crc16")]
+use crc::CRC_16_IBM_3740;
+use crc::{CRC_32_ISO_HDLC, Crc, Digest};
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+
+use crate::variant::Variant;
+use crate::{Error, Fm24v10};
+
+/// Size of the stack buffer used to checksum F-RAM contents.
+const CRC_CHUNK_BYTES: usize = 32;
+
+/// CRC-32 (ISO-HDLC, as used by Ethernet and zlib).
+pub(crate) const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+/// CRC-16 (IBM-3740, also known as CCITT-FALSE).
+#[cfg(feature = "crc16")]
+const CRC16: Crc<u16> = Crc::<u16>::new(&CRC_16_IBM_3740);
+
+/// Size of the largest checksum trailer.
+#[cfg(any(feature = "crc16", feature = "crc32"))]
+pub(crate) const MAX_CHECKSUM_BYTES: usize = 4;
+
+/// Checksum algorithm of the trailer appended by `write_checked`.
+///
+/// The algorithm is part of the stored format, so it's chosen per call rather
+/// than by a cargo feature: enabling another feature elsewhere in the
+/// dependency graph can't change how existing data is checked.
+#[cfg(any(feature = "crc16", feature = "crc32"))]
+pub trait Checksum {
+    /// Size of the trailer in bytes.
+    const BYTES: usize;
+
+    /// Writes the trailer of `data`, little endian, to the first
+    /// [`Self::BYTES`] bytes of `trailer`.
+    fn trailer(data: &[u8], trailer: &mut [u8]);
+}
+
+/// CRC-16 (IBM-3740, also known as CCITT-FALSE) trailer of 2 bytes.
+#[cfg(feature = "crc16")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Crc16;
+
+#[cfg(feature = "crc16")]
+impl Checksum for Crc16 {
+    const BYTES: usize = 2;
+
+    fn trailer(data: &[u8], trailer: &mut [u8]) {
+        trailer[..Self::BYTES].copy_from_slice(&CRC16.checksum(data).to_le_bytes());
+    }
+}
+
+/// CRC-32 (ISO-HDLC) trailer of 4 bytes.
+#[cfg(feature = "crc32")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Crc32;
+
+#[cfg(feature = "crc32")]
+impl Checksum for Crc32 {
+    const BYTES: usize = 4;
+
+    fn trailer(data: &[u8], trailer: &mut [u8]) {
+        trailer[..Self::BYTES].copy_from_slice(&CRC32.checksum(data).to_le_bytes());
+    }
+}
+
+/// Feed `len` bytes of F-RAM starting at `offset` into `digest`.
+pub(crate) async fn update_from_fram<I2C, V, WP, E>(
+    fram: &mut Fm24v10<I2C, V, WP>,
+    digest: &mut Digest<'_, u32>,
+    mut offset: u32,
+    mut len: usize,
+) -> Result<(), Error<E>>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    let mut chunk = [0u8; CRC_CHUNK_BYTES];
+    while len > 0 {
+        let n = len.min(CRC_CHUNK_BYTES);
+        fram.read(offset, &mut chunk[..n]).await?;
+        digest.update(&chunk[..n]);
+        offset += n as u32;
+        len -= n;
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! Device ID read through the reserved slave ID `0xF8`.
+//!
+//! Request framing and checking are shared by the async and blocking drivers.
+
+use embedded_hal::i2c::Error as I2cError;
+
+use crate::Error;
+use crate::variant::Variant;
+
+/// Reserved slave ID (0xF8 as 8-bit write address) used to access the Device ID.
+pub(crate) const RESERVED_SLAVE_ID: u8 = 0xF8 >> 1;
+
+/// Reserved slave ID (0x86 as 8-bit write address) that puts the device to sleep.
+#[cfg(any(feature = "sim", feature = "unstable-sleep"))]
+pub(crate) const SLEEP_SLAVE_ID: u8 = 0x86 >> 1;
+
+/// Size of the Device ID in bytes.
+pub(crate) const DEVICE_ID_BYTES: usize = 3;
+
+/// Manufacturer ID of Cypress/Infineon (formerly Ramtron) F-RAMs.
+pub const MANUFACTURER_CYPRESS: u16 = 0x004;
+
+/// The 3-byte Device ID of an I2C F-RAM.
+///
+/// The first 12 bits hold the manufacturer ID, the remaining 12 bits the
+/// product ID, whose upper nibble encodes the density.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceId {
+    /// 12-bit manufacturer ID.
+    pub manufacturer: u16,
+    /// 12-bit product ID.
+    pub product: u16,
+}
+
+impl DeviceId {
+    /// Creates a Device ID from its manufacturer and product IDs.
+    pub const fn new(manufacturer: u16, product: u16) -> Self {
+        Self {
+            manufacturer,
+            product,
+        }
+    }
+
+    /// Parses the three Device ID bytes in the order they are read from the bus.
+    pub const fn from_bytes(bytes: [u8; DEVICE_ID_BYTES]) -> Self {
+        Self {
+            manufacturer: ((bytes[0] as u16) << 4) | ((bytes[1] as u16) >> 4),
+            product: (((bytes[1] & 0x0F) as u16) << 8) | bytes[2] as u16,
+        }
+    }
+
+    /// The three Device ID bytes in the order they are sent on the bus.
+    pub const fn to_bytes(&self) -> [u8; DEVICE_ID_BYTES] {
+        [
+            (self.manufacturer >> 4) as u8,
+            (((self.manufacturer & 0x0F) << 4) | ((self.product >> 8) & 0x0F)) as u8,
+            self.product as u8,
+        ]
+    }
+
+    /// Density code (upper nibble of the product ID).
+    pub const fn density(&self) -> u8 {
+        ((self.product >> 8) & 0x0F) as u8
+    }
+}
+
+/// Byte sent to the reserved slave ID to select the device with the 7-bit
+/// `base_address`.
+pub(crate) const fn device_id_request(base_address: u8) -> [u8; 1] {
+    [base_address << 1]
+}
+
+/// The Device ID of variant `V`, or [`Error::Unsupported`] if it's unknown.
+pub(crate) fn expected_device_id<V: Variant, E: I2cError>() -> Result<DeviceId, Error<E>> {
+    V::DEVICE_ID.ok_or(Error::Unsupported)
+}
+
+/// Compares the manufacturer ID and density of `id` with `expected`, ignoring
+/// revision bits.
+pub(crate) fn check_device_id<E: I2cError>(
+    expected: DeviceId,
+    id: DeviceId,
+) -> Result<(), Error<E>> {
+    if id.manufacturer != expected.manufacturer || id.density() != expected.density() {
+        return Err(Error::DeviceIdMismatch(id));
+    }
+    Ok(())
+}
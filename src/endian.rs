@@ -0,0 +1,11 @@
+//! Byte order of multi-byte values stored in the F-RAM.
+
+/// Byte order of a multi-byte value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endian {
+    /// Least significant byte first, as used by this crate's own headers.
+    #[default]
+    Little,
+    /// Most significant byte first.
+    Big,
+}
@@ -0,0 +1,29 @@
+//! NOR flash erase emulation shared by the storage trait implementations.
+//!
+//! F-RAM has no erase cycle, erasing fills the range with `0xFF` in chunks
+//! written from a small constant buffer.
+
+use embedded_hal::i2c::Error as I2cError;
+
+use crate::Error;
+
+/// Size of the buffer used to fill erased ranges.
+const ERASE_CHUNK_BYTES: usize = 32;
+
+/// Contents of an erased chunk.
+pub(crate) const ERASED: [u8; ERASE_CHUNK_BYTES] = [0xFF; ERASE_CHUNK_BYTES];
+
+/// Checks the erase range `from..to` against `capacity` and returns the
+/// `(offset, len)` chunks to fill with [`ERASED`].
+pub(crate) fn erase_chunks<E: I2cError>(
+    from: u32,
+    to: u32,
+    capacity: usize,
+) -> Result<impl Iterator<Item = (u32, usize)>, Error<E>> {
+    if from > to || to as usize > capacity {
+        return Err(Error::OutOfBounds);
+    }
+    Ok((from..to)
+        .step_by(ERASE_CHUNK_BYTES)
+        .map(move |offset| (offset, ((to - offset) as usize).min(ERASE_CHUNK_BYTES))))
+}
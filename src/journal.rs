@@ -0,0 +1,287 @@
+//! Transactional multi-region writes with a write-ahead (redo) journal.
+//!
+//! A [`Transaction`] logs its writes into a dedicated journal region instead
+//! of touching their targets. On commit, a checksummed commit marker is
+//! written, the logged writes are applied to their targets and the journal is
+//! cleared. Should power fail on the way, [`Journal::mount`] either replays a
+//! committed journal or discards an incomplete one, so either all or none of
+//! the writes of a transaction take effect.
+//!
+//! The journal region starts with a [`JOURNAL_HEADER_BYTES`] header:
+//!
+//! | Bytes | Content                                     |
+//! |-------|---------------------------------------------|
+//! | 0     | State: `0x00` empty, `0xC3` committed       |
+//! | 1-4   | Length of the log (little endian)           |
+//! | 5-8   | CRC-32 of the log (little endian)           |
+//!
+//! followed by the log, a sequence of entries each made of the target offset
+//! (4 bytes), the data length (2 bytes, both little endian) and the data.
+
+use core::fmt::Debug;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+
+use crate::checksum::{CRC32, update_from_fram};
+use crate::variant::Variant;
+use crate::{Error, Fm24v10};
+
+/// Size of the journal header in bytes.
+pub const JOURNAL_HEADER_BYTES: usize = 9;
+
+/// Size of a log entry header (target offset and data length) in bytes.
+const ENTRY_HEADER_BYTES: usize = 6;
+
+/// State byte of a journal without a pending transaction.
+const STATE_EMPTY: u8 = 0x00;
+
+/// State byte of a journal holding a committed, possibly unapplied, transaction.
+const STATE_COMMITTED: u8 = 0xC3;
+
+/// Size of the stack buffer used to copy logged data to its target.
+const COPY_CHUNK_BYTES: usize = 32;
+
+/// What [`Journal::mount`] found in the journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// No transaction was pending.
+    Clean,
+    /// A committed transaction was (re-)applied.
+    Replayed,
+    /// An uncommitted or corrupt transaction was discarded.
+    RolledBack,
+}
+
+/// Write-ahead journal occupying a dedicated F-RAM region.
+#[derive(Debug)]
+pub struct Journal {
+    offset: u32,
+    len: usize,
+}
+
+impl Journal {
+    /// Mount the journal at `offset`, recovering any interrupted transaction.
+    ///
+    /// # Arguments
+    /// * `fram`: The F-RAM holding the journal.
+    /// * `offset`: Start of the journal region.
+    /// * `len`: Size of the journal region including the header.
+    pub async fn mount<I2C, V, WP, E>(
+        fram: &mut Fm24v10<I2C, V, WP>,
+        offset: u32,
+        len: usize,
+    ) -> Result<(Self, Recovery), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        if len <= JOURNAL_HEADER_BYTES {
+            return Err(Error::JournalFull);
+        }
+        fram.check_writable(offset, len)?;
+        let journal = Self { offset, len };
+        let recovery = journal.recover(fram).await?;
+        Ok((journal, recovery))
+    }
+
+    /// Replay a committed transaction or discard an incomplete one.
+    async fn recover<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+    ) -> Result<Recovery, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let mut header = [0u8; JOURNAL_HEADER_BYTES];
+        fram.read(self.offset, &mut header).await?;
+        let recovery = match header[0] {
+            STATE_EMPTY => Recovery::Clean,
+            STATE_COMMITTED if self.is_valid(fram, &header).await? => {
+                let log_len = u32::from_le_bytes([header[1], header[2], header[3], header[4]]);
+                self.apply(fram, log_len as usize).await?;
+                Recovery::Replayed
+            }
+            _ => Recovery::RolledBack,
+        };
+        if recovery != Recovery::Clean {
+            fram.write(self.offset, &[STATE_EMPTY]).await?;
+        }
+        Ok(recovery)
+    }
+
+    /// Start a transaction.
+    ///
+    /// Writes made through the transaction only take effect once
+    /// [`Transaction::commit`] returns. Dropping it discards them.
+    pub fn begin<'a, I2C, V, WP>(
+        &'a self,
+        fram: &'a mut Fm24v10<I2C, V, WP>,
+    ) -> Transaction<'a, I2C, V, WP> {
+        Transaction {
+            fram,
+            journal: self,
+            log_len: 0,
+        }
+    }
+
+    /// Start of the log, right after the header.
+    fn log_offset(&self) -> u32 {
+        self.offset + JOURNAL_HEADER_BYTES as u32
+    }
+
+    /// Checks a committed header's log length and checksum.
+    async fn is_valid<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        header: &[u8; JOURNAL_HEADER_BYTES],
+    ) -> Result<bool, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let log_len = u32::from_le_bytes([header[1], header[2], header[3], header[4]]) as usize;
+        let crc = u32::from_le_bytes([header[5], header[6], header[7], header[8]]);
+        if log_len > self.len - JOURNAL_HEADER_BYTES {
+            return Ok(false);
+        }
+        Ok(self.log_crc(fram, log_len).await? == crc)
+    }
+
+    /// CRC-32 of the first `log_len` bytes of the log.
+    async fn log_crc<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        log_len: usize,
+    ) -> Result<u32, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let mut digest = CRC32.digest();
+        update_from_fram(fram, &mut digest, self.log_offset(), log_len).await?;
+        Ok(digest.finalize())
+    }
+
+    /// Copy every logged write to its target.
+    async fn apply<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        log_len: usize,
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let log_end = self.log_offset() + log_len as u32;
+        let mut cursor = self.log_offset();
+        let mut chunk = [0u8; COPY_CHUNK_BYTES];
+        while cursor < log_end {
+            let mut entry = [0u8; ENTRY_HEADER_BYTES];
+            fram.read(cursor, &mut entry).await?;
+            let mut target = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+            let mut remaining = u16::from_le_bytes([entry[4], entry[5]]) as usize;
+            cursor += ENTRY_HEADER_BYTES as u32;
+
+            while remaining > 0 {
+                let n = remaining.min(COPY_CHUNK_BYTES);
+                fram.read(cursor, &mut chunk[..n]).await?;
+                fram.write(target, &chunk[..n]).await?;
+                cursor += n as u32;
+                target += n as u32;
+                remaining -= n;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A set of writes applied atomically by [`Transaction::commit`].
+pub struct Transaction<'a, I2C, V, WP> {
+    fram: &'a mut Fm24v10<I2C, V, WP>,
+    journal: &'a Journal,
+    /// Number of log bytes written so far.
+    log_len: usize,
+}
+
+impl<I2C, V, WP, E> Transaction<'_, I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    /// Log a write of `data` to `offset`.
+    ///
+    /// Returns [`Error::JournalFull`] if the entry doesn't fit into the journal,
+    /// and [`Error::OutOfBounds`] if the target overlaps the journal region.
+    pub async fn write(&mut self, offset: u32, data: &[u8]) -> Result<(), Error<E>> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.fram.check_writable(offset, data.len())?;
+        let journal_end = self.journal.offset as u64 + self.journal.len as u64;
+        if (offset as u64) < journal_end
+            && (self.journal.offset as u64) < offset as u64 + data.len() as u64
+        {
+            return Err(Error::OutOfBounds);
+        }
+        let len = u16::try_from(data.len()).map_err(|_| Error::JournalFull)?;
+        let entry_len = ENTRY_HEADER_BYTES + data.len();
+        if self.log_len + entry_len > self.journal.len - JOURNAL_HEADER_BYTES {
+            return Err(Error::JournalFull);
+        }
+
+        if self.log_len == 0 {
+            // A previous commit may have failed after its commit marker was
+            // written; finish it before its log gets overwritten.
+            self.journal.recover(self.fram).await?;
+        }
+
+        let mut entry = [0u8; ENTRY_HEADER_BYTES];
+        entry[0..4].copy_from_slice(&offset.to_le_bytes());
+        entry[4..6].copy_from_slice(&len.to_le_bytes());
+        let cursor = self.journal.log_offset() + self.log_len as u32;
+        self.fram.write(cursor, &entry).await?;
+        self.fram
+            .write(cursor + ENTRY_HEADER_BYTES as u32, data)
+            .await?;
+        self.log_len += entry_len;
+        Ok(())
+    }
+
+    /// Commit the transaction and apply its writes.
+    ///
+    /// Once the commit marker is written the transaction is durable: if applying
+    /// the writes is interrupted, the next [`Journal::mount`] finishes the job.
+    pub async fn commit(self) -> Result<(), Error<E>> {
+        if self.log_len == 0 {
+            return Ok(());
+        }
+        let crc = self.journal.log_crc(self.fram, self.log_len).await?;
+
+        // Length and checksum go in first, the single state byte written last
+        // acts as the commit marker.
+        let mut header = [0u8; JOURNAL_HEADER_BYTES - 1];
+        header[0..4].copy_from_slice(&(self.log_len as u32).to_le_bytes());
+        header[4..8].copy_from_slice(&crc.to_le_bytes());
+        self.fram.write(self.journal.offset + 1, &header).await?;
+        self.fram
+            .write(self.journal.offset, &[STATE_COMMITTED])
+            .await?;
+
+        self.journal.apply(self.fram, self.log_len).await?;
+        self.fram.write(self.journal.offset, &[STATE_EMPTY]).await
+    }
+}
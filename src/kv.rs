@@ -0,0 +1,360 @@
+//! Key-value store with fixed-size slots updated in place.
+//!
+//! F-RAM needs no erase and doesn't wear, so instead of appending to a log and
+//! compacting it like flash-based stores, every key owns a fixed-size slot that
+//! is rewritten in place. Each slot holds an 8-byte header followed by the key
+//! and the value:
+//!
+//! | Bytes | Content                                                 |
+//! |-------|---------------------------------------------------------|
+//! | 0     | State: `0xA5` in use, anything else free                |
+//! | 1     | Key length                                              |
+//! | 2-3   | Value length (little endian)                            |
+//! | 4-7   | CRC-32 of lengths, key and value (little endian)        |
+//! | 8..   | Key, then value                                         |
+//!
+//! The header is written after the key and value, and the checksum exposes an
+//! update interrupted by power loss as [`Error::RecordCorrupt`]. Combine the
+//! store with a [`crate::Journal`] or [`crate::AbSlots`] where updates must be atomic.
+
+use core::fmt::Debug;
+
+use crc::Digest;
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+
+use crate::checksum::CRC32;
+use crate::variant::Variant;
+use crate::{Error, Fm24v10};
+
+/// Size of a slot header in bytes.
+pub const KV_SLOT_HEADER_BYTES: usize = 8;
+
+/// Longest key the store accepts.
+pub const KV_MAX_KEY_BYTES: usize = 32;
+
+/// State byte of a slot in use.
+const SLOT_USED: u8 = 0xA5;
+
+/// State byte written to free a slot.
+const SLOT_FREE: u8 = 0x00;
+
+/// Parsed slot header.
+#[derive(Debug, Clone, Copy)]
+struct SlotHeader {
+    used: bool,
+    key_len: usize,
+    value_len: usize,
+    crc: u32,
+}
+
+impl SlotHeader {
+    fn parse(bytes: &[u8; KV_SLOT_HEADER_BYTES]) -> Self {
+        Self {
+            used: bytes[0] == SLOT_USED,
+            key_len: bytes[1] as usize,
+            value_len: u16::from_le_bytes([bytes[2], bytes[3]]) as usize,
+            crc: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+        }
+    }
+}
+
+/// Result of looking a key up.
+struct Lookup {
+    /// Slot holding the key.
+    found: Option<u32>,
+    /// First free slot.
+    free: Option<u32>,
+}
+
+fn slot_digest<'a>(key: &[u8], value_len: usize) -> Digest<'a, u32> {
+    let mut digest = CRC32.digest();
+    digest.update(&[key.len() as u8]);
+    digest.update(&(value_len as u16).to_le_bytes());
+    digest.update(key);
+    digest
+}
+
+/// Key-value store over a region of equally sized slots.
+#[derive(Debug, Clone)]
+pub struct KvStore {
+    offset: u32,
+    slot_bytes: usize,
+    slot_count: usize,
+}
+
+impl KvStore {
+    /// Creates a store over the `len` bytes at `offset`.
+    ///
+    /// # Arguments
+    /// * `offset`: Start of the region.
+    /// * `len`: Size of the region, split into `len / slot_bytes` slots.
+    /// * `slot_bytes`: Size of a slot, bounding key length + value length to
+    ///   `slot_bytes - KV_SLOT_HEADER_BYTES`.
+    ///
+    /// # Panics
+    /// If `slot_bytes` can't hold the header and a one-byte key.
+    pub const fn new(offset: u32, len: usize, slot_bytes: usize) -> Self {
+        assert!(slot_bytes > KV_SLOT_HEADER_BYTES);
+        Self {
+            offset,
+            slot_bytes,
+            slot_count: len / slot_bytes,
+        }
+    }
+
+    /// Number of slots, i.e. the maximum number of keys.
+    pub const fn capacity(&self) -> usize {
+        self.slot_count
+    }
+
+    /// Mark every slot free, removing all keys.
+    ///
+    /// Use this to initialise the region on first use, so stale contents aren't
+    /// mistaken for slots in use.
+    pub async fn format<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        for slot in 0..self.slot_count {
+            fram.write(self.slot_offset(slot), &[SLOT_FREE]).await?;
+        }
+        Ok(())
+    }
+
+    /// Read the value of `key` into `buf`, returning its length, or `None` if
+    /// the key isn't set.
+    ///
+    /// Returns [`Error::RecordCorrupt`] if the slot fails its checksum and
+    /// [`Error::Serialization`] if `buf` is too small for the value.
+    pub async fn get<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        key: &str,
+        buf: &mut [u8],
+    ) -> Result<Option<usize>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let key = key.as_bytes();
+        let Some(offset) = self.lookup(fram, key).await?.found else {
+            return Ok(None);
+        };
+        let mut header = [0u8; KV_SLOT_HEADER_BYTES];
+        fram.read(offset, &mut header).await?;
+        let header = SlotHeader::parse(&header);
+
+        let value = buf
+            .get_mut(..header.value_len)
+            .ok_or(Error::Serialization)?;
+        fram.read(offset + (KV_SLOT_HEADER_BYTES + key.len()) as u32, value)
+            .await?;
+        let mut digest = slot_digest(key, header.value_len);
+        digest.update(value);
+        if digest.finalize() != header.crc {
+            return Err(Error::RecordCorrupt);
+        }
+        Ok(Some(header.value_len))
+    }
+
+    /// Set `key` to `value`, overwriting the existing slot of the key in place.
+    ///
+    /// Returns [`Error::OutOfBounds`] if key and value don't fit a slot and
+    /// [`Error::StoreFull`] if the key is new and no slot is free.
+    pub async fn set<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        key: &str,
+        value: &[u8],
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let key = key.as_bytes();
+        if key.is_empty()
+            || key.len() > KV_MAX_KEY_BYTES
+            || KV_SLOT_HEADER_BYTES + key.len() + value.len() > self.slot_bytes
+        {
+            return Err(Error::OutOfBounds);
+        }
+
+        let lookup = self.lookup(fram, key).await?;
+        let offset = lookup.found.or(lookup.free).ok_or(Error::StoreFull)?;
+
+        let mut digest = slot_digest(key, value.len());
+        digest.update(value);
+        let mut header = [0u8; KV_SLOT_HEADER_BYTES];
+        header[0] = SLOT_USED;
+        header[1] = key.len() as u8;
+        header[2..4].copy_from_slice(&(value.len() as u16).to_le_bytes());
+        header[4..8].copy_from_slice(&digest.finalize().to_le_bytes());
+
+        let key_offset = offset + KV_SLOT_HEADER_BYTES as u32;
+        fram.write(key_offset, key).await?;
+        fram.write(key_offset + key.len() as u32, value).await?;
+        fram.write(offset, &header).await
+    }
+
+    /// Remove `key`, returning `true` if it was set.
+    pub async fn remove<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        key: &str,
+    ) -> Result<bool, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        match self.lookup(fram, key.as_bytes()).await?.found {
+            Some(offset) => {
+                fram.write(offset, &[SLOT_FREE]).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Iterate over all keys and values in slot order.
+    pub fn iter(&self) -> KvIter {
+        KvIter {
+            store: self.clone(),
+            slot: 0,
+        }
+    }
+
+    fn slot_offset(&self, slot: usize) -> u32 {
+        self.offset + (slot * self.slot_bytes) as u32
+    }
+
+    /// Read the header of `slot`, treating slots with impossible lengths as free.
+    async fn header<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        slot: usize,
+    ) -> Result<SlotHeader, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let mut bytes = [0u8; KV_SLOT_HEADER_BYTES];
+        fram.read(self.slot_offset(slot), &mut bytes).await?;
+        let mut header = SlotHeader::parse(&bytes);
+        if header.key_len == 0
+            || header.key_len > KV_MAX_KEY_BYTES
+            || KV_SLOT_HEADER_BYTES + header.key_len + header.value_len > self.slot_bytes
+        {
+            header.used = false;
+        }
+        Ok(header)
+    }
+
+    /// Find the slot holding `key` and the first free slot.
+    async fn lookup<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        key: &[u8],
+    ) -> Result<Lookup, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let mut lookup = Lookup {
+            found: None,
+            free: None,
+        };
+        let mut stored_key = [0u8; KV_MAX_KEY_BYTES];
+        for slot in 0..self.slot_count {
+            let header = self.header(fram, slot).await?;
+            let offset = self.slot_offset(slot);
+            if !header.used {
+                lookup.free = lookup.free.or(Some(offset));
+                continue;
+            }
+            if header.key_len != key.len() {
+                continue;
+            }
+            let stored_key = &mut stored_key[..key.len()];
+            fram.read(offset + KV_SLOT_HEADER_BYTES as u32, stored_key)
+                .await?;
+            if stored_key == key {
+                lookup.found = Some(offset);
+                break;
+            }
+        }
+        Ok(lookup)
+    }
+}
+
+/// Cursor over the entries of a [`KvStore`].
+#[derive(Debug, Clone)]
+pub struct KvIter {
+    store: KvStore,
+    slot: usize,
+}
+
+impl KvIter {
+    /// Read the next key and value, returning their lengths, or `None` at the end.
+    ///
+    /// Returns [`Error::RecordCorrupt`] if the slot fails its checksum and
+    /// [`Error::Serialization`] if a buffer is too small.
+    ///
+    /// # Arguments
+    /// * `fram`: The F-RAM holding the store.
+    /// * `key`: Buffer for the key, [`KV_MAX_KEY_BYTES`] always suffices.
+    /// * `value`: Buffer for the value.
+    pub async fn next<I2C, V, WP, E>(
+        &mut self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        key: &mut [u8],
+        value: &mut [u8],
+    ) -> Result<Option<(usize, usize)>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        while self.slot < self.store.slot_count {
+            let slot = self.slot;
+            self.slot += 1;
+            let header = self.store.header(fram, slot).await?;
+            if !header.used {
+                continue;
+            }
+
+            let key = key.get_mut(..header.key_len).ok_or(Error::Serialization)?;
+            let value = value
+                .get_mut(..header.value_len)
+                .ok_or(Error::Serialization)?;
+            let key_offset = self.store.slot_offset(slot) + KV_SLOT_HEADER_BYTES as u32;
+            fram.read(key_offset, key).await?;
+            fram.read(key_offset + header.key_len as u32, value).await?;
+
+            let mut digest = slot_digest(key, header.value_len);
+            digest.update(value);
+            if digest.finalize() != header.crc {
+                return Err(Error::RecordCorrupt);
+            }
+            return Ok(Some((header.key_len, header.value_len)));
+        }
+        Ok(None)
+    }
+}
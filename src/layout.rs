@@ -0,0 +1,157 @@
+//! Memory-mapped struct layouts with compile-time offsets.
+//!
+//! A `#[repr(C)]` struct describes the F-RAM contents, each field is accessed
+//! through a [`Field`] handle at the field's offset. With the `derive` feature,
+//! `#[derive(FramLayout)]` generates the handles as associated constants and
+//! checks at compile time that the layout fits the device, see [`FramLayout`].
+//!
+//! Fields are stored little-endian. Padding bytes between fields aren't
+//! accessed.
+
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+
+use crate::typed::MAX_SCALAR_BYTES;
+use crate::variant::Variant;
+use crate::{Endian, Error, Fm24v10, Scalar};
+
+/// A struct mapped onto the F-RAM.
+///
+/// Usually derived with the `derive` feature:
+///
+#[cfg_attr(feature = "derive", doc = "```")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// # use embedded_hal_async::i2c::I2c;
+/// # use fm24v10::{Error, Fm24v10};
+/// use fm24v10::FramLayout;
+///
+/// #[derive(FramLayout)]
+/// #[fram(offset = 0x100)]
+/// #[repr(C)]
+/// struct Config {
+///     boot_count: u32,
+///     flags: u8,
+///     serial: [u8; 16],
+/// }
+///
+/// assert_eq!(Config::SERIAL.offset(), 0x105);
+///
+/// # async fn example<I2C: I2c>(
+/// #     fram: &mut Fm24v10<I2C>,
+/// #     serial: [u8; 16],
+/// # ) -> Result<(), Error<I2C::Error>> {
+/// let boot_count = Config::BOOT_COUNT.read(fram).await?;
+/// Config::SERIAL.write(fram, &serial).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait FramLayout {
+    /// Address of the struct in the F-RAM.
+    const OFFSET: u32;
+    /// Size of the struct in bytes, including padding.
+    const BYTES: usize;
+}
+
+/// Type of a value a [`Field`] can hold: a [`Scalar`] or an array of scalars.
+pub trait FieldValue: Copy {
+    /// Type of the individual numbers.
+    type Element: Scalar;
+
+    /// The value with all bytes zero.
+    fn zeroed() -> Self;
+
+    /// The value as a slice of numbers.
+    fn elements(&self) -> &[Self::Element];
+
+    /// The value as a mutable slice of numbers.
+    fn elements_mut(&mut self) -> &mut [Self::Element];
+}
+
+impl<T: Scalar> FieldValue for T {
+    type Element = T;
+
+    fn zeroed() -> Self {
+        T::from_bytes(&[0; MAX_SCALAR_BYTES], Endian::Little)
+    }
+
+    fn elements(&self) -> &[T] {
+        core::slice::from_ref(self)
+    }
+
+    fn elements_mut(&mut self) -> &mut [T] {
+        core::slice::from_mut(self)
+    }
+}
+
+impl<T: Scalar, const N: usize> FieldValue for [T; N] {
+    type Element = T;
+
+    fn zeroed() -> Self {
+        [T::zeroed(); N]
+    }
+
+    fn elements(&self) -> &[T] {
+        self
+    }
+
+    fn elements_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+/// Handle of a value of type `T` at a fixed F-RAM address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field<T> {
+    offset: u32,
+    endian: Endian,
+    _type: PhantomData<T>,
+}
+
+impl<T: FieldValue> Field<T> {
+    /// Creates a handle of the value at `offset` stored in `endian` byte order.
+    pub const fn new(offset: u32, endian: Endian) -> Self {
+        Self {
+            offset,
+            endian,
+            _type: PhantomData,
+        }
+    }
+
+    /// Address of the value in the F-RAM.
+    pub const fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Read the value.
+    pub async fn read<I2C, V, WP, E>(&self, fram: &mut Fm24v10<I2C, V, WP>) -> Result<T, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let mut value = T::zeroed();
+        fram.read_array(self.offset, value.elements_mut(), self.endian)
+            .await?;
+        Ok(value)
+    }
+
+    /// Write `value`.
+    pub async fn write<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        value: &T,
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        fram.write_array(self.offset, value.elements(), self.endian)
+            .await
+    }
+}
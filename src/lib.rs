@@ -1,35 +1,107 @@
 #![cfg_attr(not(test), no_std)]
 
+#[cfg(feature = "sim")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::convert::Infallible;
 use core::fmt::Debug;
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use embedded_hal::digital::{ErrorType as PinErrorType, OutputPin};
+use embedded_hal_async::i2c::{Error as I2cError, ErrorKind, I2c, Operation};
+
+mod address;
+mod array;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(any(feature = "crc16", feature = "crc32"))]
+mod checked;
+mod checksum;
+mod device_id;
+mod endian;
+mod erase;
+mod journal;
+mod kv;
+mod layout;
+mod modify;
+mod partition;
+mod protect;
+mod reader;
+#[cfg(feature = "postcard")]
+mod record;
+mod ring_log;
+#[cfg(feature = "sim")]
+pub mod sim;
+mod slots;
+mod storage;
+mod typed;
+pub mod variant;
+
+pub use address::Address;
+use address::{MemoryAddress, check_bounds, slave_address};
+pub use array::Fm24v10Array;
+#[cfg(any(feature = "crc16", feature = "crc32"))]
+pub use checksum::Checksum;
+#[cfg(feature = "crc16")]
+pub use checksum::Crc16;
+#[cfg(feature = "crc32")]
+pub use checksum::Crc32;
+#[cfg(feature = "unstable-sleep")]
+use device_id::SLEEP_SLAVE_ID;
+use device_id::{
+    DEVICE_ID_BYTES, RESERVED_SLAVE_ID, check_device_id, device_id_request, expected_device_id,
+};
+pub use device_id::{DeviceId, MANUFACTURER_CYPRESS};
+pub use endian::Endian;
+#[cfg(feature = "derive")]
+pub use fm24v10_derive::FramLayout;
+pub use journal::{JOURNAL_HEADER_BYTES, Journal, Recovery, Transaction};
+pub use kv::{KV_MAX_KEY_BYTES, KV_SLOT_HEADER_BYTES, KvIter, KvStore};
+pub use layout::{Field, FieldValue, FramLayout};
+pub use partition::{BoundPartition, Partition, PartitionHandle, PartitionTable};
+use protect::ProtectedRegions;
+pub use protect::{MAX_PROTECTED_REGIONS, ProvisioningToken};
+pub use reader::Reader;
+#[cfg(feature = "postcard")]
+pub use record::RECORD_HEADER_BYTES;
+pub use ring_log::{RING_LOG_ENTRY_HEADER_BYTES, RING_LOG_STATE_BYTES, RingLog, RingLogIter};
+pub use slots::{AbSlots, SLOT_HEADER_BYTES};
+pub use typed::Scalar;
+use variant::Variant;
 
-use embedded_hal_async::i2c::{Error as I2cError, I2c};
+/// Placeholder for drivers without a write-protect pin.
+///
+/// All pin operations succeed without doing anything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoPin;
 
-/// Represents the hardware address selection pins A1 and A2 for the FM24V10.
-/// The tuple elements are expected to be 0 or 1, corresponding to the pin states.
-/// Address.0 corresponds to A1 pin state.
-/// Address.1 corresponds to A2 pin state.
-#[derive(Debug, Clone, Copy)]
-pub struct Address(pub u8, pub u8);
+impl PinErrorType for NoPin {
+    type Error = Infallible;
+}
 
-/// Device Type code for FM24V10 family (4-bit prefix: 1010b).
-const DEVICE_TYPE_CODE: u8 = 0b1010;
+impl OutputPin for NoPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
 
-impl From<Address> for u8 {
-    fn from(a: Address) -> Self {
-        // Constructs the base 7-bit I2C address part: 0b1010_A2A1_0
-        // DEVICE_TYPE_CODE (0b1010) is shifted to become 0b1010_000.
-        // a.1 (A2 pin state) is shifted to 0b000_A200.
-        // a.0 (A1 pin state) is shifted to 0b000_0A10.
-        (DEVICE_TYPE_CODE << 3) | (a.1 << 2) | (a.0 << 1)
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
     }
 }
 
-/// Number of memory address bytes (A15-A0) sent after the slave address byte.
-/// The FM24V10 uses 2 bytes for the 16 lower bits of its 17-bit address.
-const MEMORY_ADDRESS_BYTES: usize = 2;
+/// Number of buffers transferred per I2C transaction by
+/// [`Fm24v10::read_vectored`] and [`Fm24v10::write_vectored`].
+///
+/// More buffers are split into several transactions, each starting with the
+/// memory address.
+pub const MAX_BUFFERS_PER_TRANSACTION: usize = 8;
 
-/// Capacity of the FM24V10 in bytes (1Mbit = 128KB).
-const CAPACITY_BYTES: usize = 128 * 1024; // 131,072 bytes
+/// Number of times the slave address is polled while the device recovers from
+/// sleep (tREC, 400us max) before giving up.
+const WAKE_POLL_ATTEMPTS: usize = 256;
 
 /// Custom error type for the FM24V10 driver.
 #[derive(Debug)]
@@ -39,21 +111,53 @@ pub enum Error<E: Debug + I2cError> {
     I2c(E),
     /// Address or data length is out of bounds
     OutOfBounds,
-    /// The user-provided buffer is too small for the current write operation.
-    BufferTooSmall,
+    /// The Device ID read from the part doesn't match the expected variant.
+    DeviceIdMismatch(DeviceId),
+    /// The operation isn't supported by the device variant.
+    Unsupported,
+    /// The device didn't acknowledge the sleep command.
+    SleepNotAcknowledged,
+    /// The device didn't respond within the wake-up polling limit.
+    WakeTimeout,
+    /// Driving the write-protect pin failed.
+    WriteProtectPin,
+    /// The write touches a software write-protected region.
+    WriteProtected,
+    /// All [`MAX_PROTECTED_REGIONS`] protected region slots are in use.
+    TooManyProtectedRegions,
+    /// A value couldn't be encoded or decoded, or doesn't fit the buffer.
+    Serialization,
+    /// No valid record is stored at the given location.
+    RecordMissing,
+    /// The stored record has an invalid length or checksum.
+    RecordCorrupt,
+    /// The data read doesn't match its stored checksum.
+    ChecksumMismatch,
+    /// The transaction doesn't fit into the journal region.
+    JournalFull,
+    /// No free slot is left in the key-value store.
+    StoreFull,
 }
 
-/// Driver for the FM24V10 I2C F-RAM
-pub struct Fm24v10<'buf, I2C> {
+/// Driver for the FM24V10 and related I2C F-RAMs
+pub struct Fm24v10<I2C, V = variant::Fm24v10, WP = NoPin> {
     i2c: I2C,
-    /// Base I2C address part (0b1010_A2A1_0), derived from device type and A2/A1 pins.
-    /// The page select bit (A16) will be ORed with this to get the final 7-bit slave address.
+    /// Base I2C address part (0b1010_A2A1A0), derived from device type and the
+    /// address pins the variant has. The page select bits will be ORed with this
+    /// to get the final 7-bit slave address.
     base_address: u8,
-    /// User-provided buffer for constructing I2C write payloads.
-    write_buffer: &'buf mut [u8],
+    /// Whether the device was put into sleep mode by [`Fm24v10::sleep`].
+    sleeping: bool,
+    /// Write-protect pin, held high (protected) except during writes.
+    write_protect: WP,
+    /// Whether the write-protect pin was released by [`Fm24v10::unlock`].
+    unlocked: bool,
+    /// Address ranges that [`Fm24v10::write`] refuses to touch.
+    protected: ProtectedRegions,
+    _variant: PhantomData<V>,
 }
 
-impl<'buf, I2C, E> Fm24v10<'buf, I2C>
+impl<I2C, E> Fm24v10<I2C>
 where
     I2C: I2c<Error = E>,
     E: Debug + I2cError,
@@ -62,34 +166,75 @@ where
     ///
     /// # Arguments
     /// * `i2c`: The I2C bus peripheral.
-    /// * `address_pins`: The state of the A2 and A1 hardware address pins,
-    ///   as `Address(a1_pin_state, a2_pin_state)`.
-    /// * `write_buffer`: A mutable slice provided by the user, used for assembling
-    ///   I2C write payloads. It must be large enough to hold
-    ///   `MEMORY_ADDRESS_BYTES` + the largest anticipated data write.
-    pub fn new(i2c: I2C, address_pins: Address, write_buffer: &'buf mut [u8]) -> Self {
+    /// * `address_pins`: The state of the A2 and A1 hardware address pins.
+    pub fn new(i2c: I2C, address_pins: Address) -> Self {
+        Self::new_variant(i2c, variant::Fm24v10, address_pins)
+    }
+}
+
+impl<I2C, V, E> Fm24v10<I2C, V>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    E: Debug + I2cError,
+{
+    /// Creates a new driver instance for another member of the F-RAM family.
+    ///
+    /// # Arguments
+    /// * `i2c`: The I2C bus peripheral.
+    /// * `_variant`: The device variant, e.g. `variant::Fm24cl16b`.
+    /// * `address_pins`: The state of the hardware address pins. Pins the
+    ///   variant doesn't have are ignored.
+    pub fn new_variant(i2c: I2C, _variant: V, address_pins: Address) -> Self {
         Self {
             i2c,
-            base_address: address_pins.into(),
-            write_buffer,
+            base_address: address::base_address::<V>(address_pins),
+            sleeping: false,
+            write_protect: NoPin,
+            unlocked: false,
+            protected: ProtectedRegions::default(),
+            _variant: PhantomData,
         }
     }
 
-    /// Constructs the final 7-bit I2C device address for a given memory operation.
-    /// It combines the base address (derived from device type and A1/A2 pins)
-    /// with the page select bit (A16 of the memory address).
-    fn get_address_for_offset(&self, memory_offset: u32) -> Result<u8, Error<E>> {
-        if memory_offset >= CAPACITY_BYTES as u32 {
-            return Err(Error::OutOfBounds);
-        }
-        // A16 is the most significant bit of the 17-bit memory address, acts as page select.
-        // It's the LSB of the OR operation with base_address (which has LSB as 0).
-        let page_select_bit = (memory_offset >> 16) & 0x01;
+    /// Hand the WP pin to the driver.
+    ///
+    /// The pin is driven high (write-protected) right away and only released
+    /// for the duration of each write, unless unlocked with [`Fm24v10::unlock`].
+    pub fn with_write_protect<WP: OutputPin>(
+        self,
+        mut write_protect: WP,
+    ) -> Result<Fm24v10<I2C, V, WP>, Error<E>> {
+        write_protect
+            .set_high()
+            .map_err(|_| Error::WriteProtectPin)?;
+        Ok(Fm24v10 {
+            i2c: self.i2c,
+            base_address: self.base_address,
+            sleeping: self.sleeping,
+            write_protect,
+            unlocked: false,
+            protected: self.protected,
+            _variant: PhantomData,
+        })
+    }
 
-        let final_address = self.base_address | (page_select_bit as u8);
-        Ok(final_address)
+    /// Destroys the driver and returns the I2C bus.
+    ///
+    /// Drivers with a write-protect pin are released with
+    /// [`Fm24v10::release_with_pin`].
+    pub fn release(self) -> I2C {
+        self.i2c
     }
+}
 
+impl<I2C, V, WP, E> Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
     /// Read a slice of data from the F-RAM.
     ///
     /// # Arguments
@@ -99,29 +244,222 @@ where
         if bytes.is_empty() {
             return Ok(());
         }
-        if offset >= CAPACITY_BYTES as u32 || bytes.len() > (CAPACITY_BYTES - offset as usize) {
-            return Err(Error::OutOfBounds);
+        check_bounds::<V, E>(offset, bytes.len())?;
+
+        self.ensure_awake().await?;
+        let address = slave_address::<V>(self.base_address, offset);
+        let mem_addr_payload = MemoryAddress::new::<V>(offset);
+
+        self.i2c
+            .write_read(address, mem_addr_payload.as_bytes(), bytes)
+            .await
+            .map_err(Error::I2c)?;
+        Ok(())
+    }
+
+    /// Read consecutive bytes into several buffers, in order.
+    ///
+    /// The buffers are filled by read operations of a single I2C transaction,
+    /// up to [`MAX_BUFFERS_PER_TRANSACTION`] at a time, so no intermediate copy
+    /// is made.
+    ///
+    /// # Arguments
+    /// * `offset`: The starting memory address offset to read from (0 to CAPACITY_BYTES - 1).
+    /// * `bytes`: The buffers to store the read data.
+    pub async fn read_vectored(
+        &mut self,
+        mut offset: u32,
+        bytes: &mut [&mut [u8]],
+    ) -> Result<(), Error<E>> {
+        let len = bytes.iter().map(|buf| buf.len()).sum();
+        if len == 0 {
+            return Ok(());
         }
+        check_bounds::<V, E>(offset, len)?;
+
+        self.ensure_awake().await?;
+        let mut buffers = bytes.iter_mut().filter(|buf| !buf.is_empty()).peekable();
+        while buffers.peek().is_some() {
+            let address = slave_address::<V>(self.base_address, offset);
+            let mem_addr_payload = MemoryAddress::new::<V>(offset);
+            let mut operations: [Operation<'_>; MAX_BUFFERS_PER_TRANSACTION + 1] =
+                core::array::from_fn(|_| Operation::Write(&[]));
+            operations[0] = Operation::Write(mem_addr_payload.as_bytes());
+            let mut count = 1;
+            for (operation, buf) in operations[1..].iter_mut().zip(buffers.by_ref()) {
+                offset += buf.len() as u32;
+                *operation = Operation::Read(buf);
+                count += 1;
+            }
 
-        let address = self.get_address_for_offset(offset)?;
-        // Memory address bytes (A15-A0) to be sent.
-        let mem_addr_payload: [u8; MEMORY_ADDRESS_BYTES] =
-            [((offset >> 8) & 0xFF) as u8, (offset & 0xFF) as u8];
+            // Adjacent read operations continue without a repeated start.
+            self.i2c
+                .transaction(address, &mut operations[..count])
+                .await
+                .map_err(Error::I2c)?;
+        }
+        Ok(())
+    }
 
+    /// Read the 3-byte Device ID through the reserved slave ID 0xF8.
+    ///
+    /// The reserved slave ID is followed by this device's slave address, then
+    /// the ID is read back after a repeated start.
+    pub async fn device_id(&mut self) -> Result<DeviceId, Error<E>> {
+        self.ensure_awake().await?;
+        let mut id = [0u8; DEVICE_ID_BYTES];
+        self.i2c
+            .write_read(
+                RESERVED_SLAVE_ID,
+                &device_id_request(self.base_address),
+                &mut id,
+            )
+            .await
+            .map_err(Error::I2c)?;
+        Ok(DeviceId::from_bytes(id))
+    }
+
+    /// Check that the connected part is the expected variant.
+    ///
+    /// The manufacturer ID and density of the Device ID are compared, revision
+    /// bits are ignored. Returns [`Error::Unsupported`] for variants without a
+    /// known Device ID.
+    pub async fn verify(&mut self) -> Result<(), Error<E>> {
+        let expected = expected_device_id::<V, E>()?;
+        let id = self.device_id().await?;
+        check_device_id(expected, id)
+    }
+
+    /// Put the device into its low-power sleep mode.
+    ///
+    /// The reserved slave ID 0xF8 followed by this device's slave address is
+    /// sent, then the sleep command 0x86. The datasheet puts a repeated start
+    /// before 0x86, but embedded-hal can't address two slaves within one
+    /// transaction, so the two parts are separated by a stop condition instead.
+    ///
+    /// This deviation hasn't been verified on hardware, so the method is only
+    /// available with the `unstable-sleep` feature. A part that ignores the
+    /// sleep command after the stop doesn't acknowledge it, which is reported
+    /// as [`Error::SleepNotAcknowledged`] and leaves the device awake.
+    ///
+    /// Subsequent reads and writes wake the device up automatically.
+    #[cfg(feature = "unstable-sleep")]
+    pub async fn sleep(&mut self) -> Result<(), Error<E>> {
+        self.ensure_awake().await?;
         self.i2c
-            .write_read(address, &mem_addr_payload, bytes)
+            .write(RESERVED_SLAVE_ID, &device_id_request(self.base_address))
             .await
             .map_err(Error::I2c)?;
+        self.i2c
+            .write(SLEEP_SLAVE_ID, &[])
+            .await
+            .map_err(|e| match e.kind() {
+                ErrorKind::NoAcknowledge(_) => Error::SleepNotAcknowledged,
+                _ => Error::I2c(e),
+            })?;
+        self.sleeping = true;
         Ok(())
     }
 
+    /// Wake the device up from sleep mode.
+    ///
+    /// The device is woken by addressing it, which it doesn't acknowledge. It
+    /// then needs up to tREC to recover, during which the slave address is
+    /// polled until the device acknowledges it again.
+    pub async fn wake(&mut self) -> Result<(), Error<E>> {
+        // The dummy start is expected to be NAKed, as is polling during tREC.
+        // Other bus errors are reported.
+        match self.i2c.write(self.base_address, &[]).await {
+            Err(e) if !matches!(e.kind(), ErrorKind::NoAcknowledge(_)) => {
+                return Err(Error::I2c(e));
+            }
+            _ => {}
+        }
+        for _ in 0..WAKE_POLL_ATTEMPTS {
+            match self.i2c.write(self.base_address, &[]).await {
+                Ok(()) => {
+                    self.sleeping = false;
+                    return Ok(());
+                }
+                Err(e) if matches!(e.kind(), ErrorKind::NoAcknowledge(_)) => {}
+                Err(e) => return Err(Error::I2c(e)),
+            }
+        }
+        Err(Error::WakeTimeout)
+    }
+
+    /// Returns `true` if the device was put to sleep and hasn't been woken yet.
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    /// Wakes the device up if it was put to sleep.
+    async fn ensure_awake(&mut self) -> Result<(), Error<E>> {
+        if self.sleeping {
+            self.wake().await?;
+        }
+        Ok(())
+    }
+
+    /// Drive the write-protect pin high, protecting the whole array.
+    pub fn lock(&mut self) -> Result<(), Error<E>> {
+        self.write_protect
+            .set_high()
+            .map_err(|_| Error::WriteProtectPin)?;
+        self.unlocked = false;
+        Ok(())
+    }
+
+    /// Drive the write-protect pin low until [`Fm24v10::lock`] is called.
+    ///
+    /// Useful to avoid toggling the pin around every write of a larger update.
+    pub fn unlock(&mut self) -> Result<(), Error<E>> {
+        self.write_protect
+            .set_low()
+            .map_err(|_| Error::WriteProtectPin)?;
+        self.unlocked = true;
+        Ok(())
+    }
+
+    /// Mark an address range as read-only in software.
+    ///
+    /// Writes overlapping the range fail with [`Error::WriteProtected`] until the
+    /// regions are cleared with [`Fm24v10::clear_protected_regions`]. Use
+    /// [`Fm24v10::write_provisioning`] to write to protected ranges.
+    pub fn protect(&mut self, range: Range<u32>) -> Result<(), Error<E>> {
+        self.protected.add::<V, E>(range)
+    }
+
+    /// Remove all software write-protected regions.
+    pub fn clear_protected_regions(&mut self, _token: &ProvisioningToken) {
+        self.protected.clear();
+    }
+
+    /// Destroys the driver and returns the I2C bus and the write-protect pin.
+    ///
+    /// The pin is left as it is, high unless the driver was unlocked.
+    pub fn release_with_pin(self) -> (I2C, WP) {
+        (self.i2c, self.write_protect)
+    }
+
     /// Get the total capacity of the F-RAM in bytes.
     pub async fn capacity(&self) -> Result<usize, Error<E>> {
-        Ok(CAPACITY_BYTES)
+        Ok(V::CAPACITY_BYTES)
     }
 
     /// Write a slice of data to the F-RAM.
     ///
+    /// The memory address and the data are sent as two write operations of a
+    /// single I2C transaction, so no intermediate copy of `data` is made and
+    /// writes of any size up to the capacity are possible.
+    ///
+    /// embedded-hal requires adjacent write operations to be sent without a
+    /// repeated start in between, which this relies on. Some HALs, e.g.
+    /// `linux-embedded-hal`, put a repeated start between them anyway, and the
+    /// device takes the first data bytes as a new memory address. Use the
+    /// `blocking::Fm24v10` driver of the `blocking` feature with those, it
+    /// sends every chunk with a single write.
+    ///
     /// # Arguments
     /// * `offset`: The starting memory address offset to write to (0 to CAPACITY_BYTES - 1).
     /// * `data`: The slice of data to write.
@@ -129,29 +467,109 @@ where
         if data.is_empty() {
             return Ok(());
         }
-        if offset >= CAPACITY_BYTES as u32 || data.len() > (CAPACITY_BYTES - offset as usize) {
-            return Err(Error::OutOfBounds);
+        self.check_writable(offset, data.len())?;
+
+        self.write_unchecked(offset, &[data]).await
+    }
+
+    /// Write several buffers to consecutive bytes of the F-RAM, in order.
+    ///
+    /// The buffers are sent as write operations of a single I2C transaction, up
+    /// to [`MAX_BUFFERS_PER_TRANSACTION`] at a time, so e.g. a header and a
+    /// payload can be written without copying them into one slice first. The
+    /// HAL has to send adjacent write operations without a repeated start, see
+    /// [`Fm24v10::write`].
+    ///
+    /// # Arguments
+    /// * `offset`: The starting memory address offset to write to (0 to CAPACITY_BYTES - 1).
+    /// * `data`: The buffers to write.
+    pub async fn write_vectored(&mut self, offset: u32, data: &[&[u8]]) -> Result<(), Error<E>> {
+        let len = data.iter().map(|buf| buf.len()).sum();
+        if len == 0 {
+            return Ok(());
         }
+        self.check_writable(offset, len)?;
+
+        self.write_unchecked(offset, data).await
+    }
 
-        let required_buffer_len = MEMORY_ADDRESS_BYTES + data.len();
-        if self.write_buffer.len() < required_buffer_len {
-            return Err(Error::BufferTooSmall);
+    /// Checks that `len` bytes at `offset` are in bounds and not write-protected.
+    pub(crate) fn check_writable(&self, offset: u32, len: usize) -> Result<(), Error<E>> {
+        self.protected.check_writable::<V, E>(offset, len)
+    }
+
+    /// Write a slice of data to the F-RAM, ignoring software write protection.
+    ///
+    /// Intended for factory provisioning of otherwise read-only regions. The
+    /// hardware write-protect pin is still handled as in [`Fm24v10::write`].
+    ///
+    /// # Arguments
+    /// * `_token`: Proof that bypassing the protection is intended.
+    /// * `offset`: The starting memory address offset to write to (0 to CAPACITY_BYTES - 1).
+    /// * `data`: The slice of data to write.
+    pub async fn write_provisioning(
+        &mut self,
+        _token: &ProvisioningToken,
+        offset: u32,
+        data: &[u8],
+    ) -> Result<(), Error<E>> {
+        if data.is_empty() {
+            return Ok(());
         }
+        check_bounds::<V, E>(offset, data.len())?;
 
-        let i2c_7bit_address = self.get_address_for_offset(offset)?;
+        self.write_unchecked(offset, &[data]).await
+    }
 
-        // MSB of memory address (A15-A8)
-        self.write_buffer[0] = ((offset >> 8) & 0xFF) as u8;
-        // LSB of memory address (A7-A0)
-        self.write_buffer[1] = (offset & 0xFF) as u8;
-        // Data slice
-        self.write_buffer[MEMORY_ADDRESS_BYTES..required_buffer_len].copy_from_slice(data);
+    /// Performs a bounds-checked write, handling wake-up and the WP pin.
+    async fn write_unchecked(&mut self, offset: u32, data: &[&[u8]]) -> Result<(), Error<E>> {
+        self.ensure_awake().await?;
 
-        self.i2c
-            .write(i2c_7bit_address, &self.write_buffer[..required_buffer_len])
-            .await
-            .map_err(Error::I2c)?;
+        if !self.unlocked {
+            self.write_protect
+                .set_low()
+                .map_err(|_| Error::WriteProtectPin)?;
+        }
 
+        let result = self.write_transactions(offset, data).await;
+
+        if !self.unlocked {
+            self.write_protect
+                .set_high()
+                .map_err(|_| Error::WriteProtectPin)?;
+        }
+
+        result
+    }
+
+    /// Sends `data` in transactions of up to [`MAX_BUFFERS_PER_TRANSACTION`]
+    /// buffers each.
+    async fn write_transactions(
+        &mut self,
+        mut offset: u32,
+        data: &[&[u8]],
+    ) -> Result<(), Error<E>> {
+        let mut buffers = data.iter().filter(|buf| !buf.is_empty()).peekable();
+        while buffers.peek().is_some() {
+            let i2c_7bit_address = slave_address::<V>(self.base_address, offset);
+            let mem_addr_payload = MemoryAddress::new::<V>(offset);
+            let mut operations: [Operation<'_>; MAX_BUFFERS_PER_TRANSACTION + 1] =
+                core::array::from_fn(|_| Operation::Write(&[]));
+            operations[0] = Operation::Write(mem_addr_payload.as_bytes());
+            let mut count = 1;
+            for (operation, buf) in operations[1..].iter_mut().zip(buffers.by_ref()) {
+                offset += buf.len() as u32;
+                *operation = Operation::Write(buf);
+                count += 1;
+            }
+
+            // Adjacent write operations are sent back to back without a repeated
+            // start, so the device sees one contiguous address + data payload.
+            self.i2c
+                .transaction(i2c_7bit_address, &mut operations[..count])
+                .await
+                .map_err(Error::I2c)?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,79 @@
+//! Read-modify-write helpers for flags and counters.
+//!
+//! Each helper reads the affected bytes, modifies them and writes back only the
+//! span between the first and last byte that actually changed. Nothing is
+//! written if the value is unchanged. A call isn't atomic with respect to other
+//! bus masters, but no other access through the same driver can interleave.
+
+use core::fmt::Debug;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+
+use crate::variant::Variant;
+use crate::{Endian, Error, Fm24v10, Scalar};
+
+impl<I2C, V, WP, E> Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    /// Read `N` bytes at `offset`, let `f` modify them and write back the
+    /// changed bytes.
+    ///
+    /// # Arguments
+    /// * `offset`: The memory address offset of the first byte.
+    /// * `f`: Closure modifying the bytes in place.
+    pub async fn update<const N: usize>(
+        &mut self,
+        offset: u32,
+        f: impl FnOnce(&mut [u8; N]),
+    ) -> Result<(), Error<E>> {
+        let mut old = [0u8; N];
+        self.read(offset, &mut old).await?;
+        let mut new = old;
+        f(&mut new);
+
+        let Some(first) = old.iter().zip(&new).position(|(a, b)| a != b) else {
+            return Ok(());
+        };
+        let last = old
+            .iter()
+            .zip(&new)
+            .rposition(|(a, b)| a != b)
+            .unwrap_or(first);
+        self.write(offset + first as u32, &new[first..=last]).await
+    }
+
+    /// Set the bits in `mask` of the byte at `offset`.
+    pub async fn set_bits(&mut self, offset: u32, mask: u8) -> Result<(), Error<E>> {
+        self.update(offset, |[byte]: &mut [u8; 1]| *byte |= mask)
+            .await
+    }
+
+    /// Clear the bits in `mask` of the byte at `offset`.
+    pub async fn clear_bits(&mut self, offset: u32, mask: u8) -> Result<(), Error<E>> {
+        self.update(offset, |[byte]: &mut [u8; 1]| *byte &= !mask)
+            .await
+    }
+
+    /// Increment the `u32` counter at `offset`, wrapping around on overflow,
+    /// and return the new value.
+    ///
+    /// Only the bytes affected by the carry are rewritten, usually just one.
+    ///
+    /// # Arguments
+    /// * `offset`: The memory address offset of the counter.
+    /// * `endian`: The byte order the counter is stored in.
+    pub async fn increment_u32(&mut self, offset: u32, endian: Endian) -> Result<u32, Error<E>> {
+        let mut value = 0;
+        self.update(offset, |bytes: &mut [u8; 4]| {
+            value = u32::from_bytes(bytes, endian).wrapping_add(1);
+            value.to_bytes(bytes, endian);
+        })
+        .await?;
+        Ok(value)
+    }
+}
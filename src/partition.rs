@@ -0,0 +1,283 @@
+//! Partitioning the F-RAM among independent subsystems.
+//!
+//! A [`PartitionTable`] is defined at compile time for a device variant;
+//! overlapping partitions or partitions not fitting the device fail const
+//! evaluation. Each subsystem then gets a [`PartitionHandle`] from the table,
+//! which offers zero-based `read`/`write` bounded to the partition. Handles
+//! don't borrow the driver, so a subsystem can keep its handle and pass the
+//! driver to each call, like the other storage layers of this crate.
+//! [`PartitionHandle::bind`] pairs a handle with the driver for use with the
+//! [`embedded_storage_async`] NOR flash traits.
+
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+use embedded_storage_async::nor_flash::{ErrorType, MultiwriteNorFlash, NorFlash, ReadNorFlash};
+
+use crate::variant::Variant;
+use crate::{Error, Fm24v10};
+
+/// A contiguous address range of the F-RAM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Partition {
+    offset: u32,
+    len: usize,
+}
+
+impl Partition {
+    /// Creates a partition of `len` bytes starting at `offset`.
+    pub const fn new(offset: u32, len: usize) -> Self {
+        Self { offset, len }
+    }
+
+    /// Creates a partition of `len` bytes starting right after this one.
+    pub const fn after(&self, len: usize) -> Self {
+        Self::new(self.end(), len)
+    }
+
+    /// Start of the partition.
+    pub const fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Size of the partition in bytes.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the partition is zero bytes long.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// First address after the partition.
+    pub const fn end(&self) -> u32 {
+        self.offset + self.len as u32
+    }
+
+    const fn overlaps(&self, other: &Partition) -> bool {
+        self.offset < other.end() && other.offset < self.end()
+    }
+}
+
+/// A set of non-overlapping partitions of a device variant `V`.
+#[derive(Debug, Clone, Copy)]
+pub struct PartitionTable<V, const N: usize> {
+    partitions: [Partition; N],
+    _variant: PhantomData<V>,
+}
+
+impl<V: Variant, const N: usize> PartitionTable<V, N> {
+    /// Creates a partition table for variant `V`.
+    ///
+    /// # Panics
+    /// If partitions overlap or exceed the capacity of `V`. Evaluated in a
+    /// `const`, this is a compile-time error.
+    pub const fn new(partitions: [Partition; N]) -> Self {
+        let mut i = 0;
+        while i < N {
+            assert!(
+                partitions[i].end() as usize <= V::CAPACITY_BYTES,
+                "partition exceeds the device capacity"
+            );
+            let mut j = i + 1;
+            while j < N {
+                assert!(
+                    !partitions[i].overlaps(&partitions[j]),
+                    "partitions overlap"
+                );
+                j += 1;
+            }
+            i += 1;
+        }
+        Self {
+            partitions,
+            _variant: PhantomData,
+        }
+    }
+
+    /// The partition at `index`.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    pub const fn get(&self, index: usize) -> Partition {
+        self.partitions[index]
+    }
+
+    /// All partitions, in definition order.
+    pub const fn partitions(&self) -> &[Partition; N] {
+        &self.partitions
+    }
+
+    /// A handle to access the partition at `index`.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    pub const fn handle(&self, index: usize) -> PartitionHandle<V> {
+        PartitionHandle {
+            partition: self.partitions[index],
+            _variant: PhantomData,
+        }
+    }
+}
+
+/// Zero-based access to a single partition of a [`PartitionTable`].
+///
+/// Reads and writes outside the partition fail with [`Error::OutOfBounds`].
+#[derive(Debug)]
+pub struct PartitionHandle<V> {
+    partition: Partition,
+    _variant: PhantomData<V>,
+}
+
+// Not derived, which would require `V: Copy`.
+impl<V> Clone for PartitionHandle<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for PartitionHandle<V> {}
+
+impl<V: Variant> PartitionHandle<V> {
+    /// The partition accessed through the handle.
+    pub const fn partition(&self) -> Partition {
+        self.partition
+    }
+
+    /// Get the size of the partition in bytes.
+    pub const fn capacity(&self) -> usize {
+        self.partition.len
+    }
+
+    /// Translates a partition offset into a device offset.
+    fn device_offset<E: Debug + I2cError>(&self, offset: u32, len: usize) -> Result<u32, Error<E>> {
+        if offset as usize > self.partition.len || len > self.partition.len - offset as usize {
+            return Err(Error::OutOfBounds);
+        }
+        Ok(self.partition.offset + offset)
+    }
+
+    /// Read a slice of data from the partition.
+    ///
+    /// # Arguments
+    /// * `fram`: The F-RAM holding the partition.
+    /// * `offset`: The starting offset within the partition.
+    /// * `bytes`: A mutable slice to store the read data.
+    pub async fn read<I2C, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        offset: u32,
+        bytes: &mut [u8],
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let offset = self.device_offset(offset, bytes.len())?;
+        fram.read(offset, bytes).await
+    }
+
+    /// Write a slice of data to the partition.
+    ///
+    /// # Arguments
+    /// * `fram`: The F-RAM holding the partition.
+    /// * `offset`: The starting offset within the partition.
+    /// * `data`: The slice of data to write.
+    pub async fn write<I2C, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        offset: u32,
+        data: &[u8],
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let offset = self.device_offset(offset, data.len())?;
+        fram.write(offset, data).await
+    }
+
+    /// Pair the handle with the driver, e.g. to pass the partition to code
+    /// using the NOR flash traits.
+    pub fn bind<'a, I2C, WP>(
+        &self,
+        fram: &'a mut Fm24v10<I2C, V, WP>,
+    ) -> BoundPartition<'a, I2C, V, WP> {
+        BoundPartition {
+            fram,
+            handle: *self,
+        }
+    }
+}
+
+/// A [`PartitionHandle`] paired with the driver, see [`PartitionHandle::bind`].
+pub struct BoundPartition<'a, I2C, V, WP> {
+    fram: &'a mut Fm24v10<I2C, V, WP>,
+    handle: PartitionHandle<V>,
+}
+
+impl<I2C, V, WP, E> ErrorType for BoundPartition<'_, I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    type Error = Error<E>;
+}
+
+impl<I2C, V, WP, E> ReadNorFlash for BoundPartition<'_, I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    const READ_SIZE: usize = 1;
+
+    async fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        self.handle.read(self.fram, offset, bytes).await
+    }
+
+    fn capacity(&self) -> usize {
+        self.handle.capacity()
+    }
+}
+
+impl<I2C, V, WP, E> NorFlash for BoundPartition<'_, I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    const WRITE_SIZE: usize = 1;
+    const ERASE_SIZE: usize = 1;
+
+    async fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        if from > to {
+            return Err(Error::OutOfBounds);
+        }
+        let len = to - from;
+        let from = self.handle.device_offset(from, len as usize)?;
+        NorFlash::erase(self.fram, from, from + len).await
+    }
+
+    async fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.handle.write(self.fram, offset, bytes).await
+    }
+}
+
+impl<I2C, V, WP, E> MultiwriteNorFlash for BoundPartition<'_, I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+}
@@ -0,0 +1,88 @@
+//! Software write-protected address ranges.
+//!
+//! The checks are shared by the async and blocking drivers.
+
+use core::ops::Range;
+
+use embedded_hal::i2c::Error as I2cError;
+
+use crate::Error;
+use crate::address::check_bounds;
+use crate::variant::Variant;
+
+/// Maximum number of protected regions a driver can hold.
+pub const MAX_PROTECTED_REGIONS: usize = 4;
+
+/// Token required to write to protected regions or to remove them.
+///
+/// Meant for factory provisioning: constructing it is deliberately explicit,
+/// so bypassing the software write protection stands out at the call site.
+#[derive(Debug)]
+pub struct ProvisioningToken {
+    _private: (),
+}
+
+impl ProvisioningToken {
+    /// Creates a token that allows bypassing software write protection.
+    pub const fn new() -> Self {
+        Self { _private: () }
+    }
+}
+
+impl Default for ProvisioningToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fixed-capacity set of read-only address ranges.
+#[derive(Debug, Default)]
+pub(crate) struct ProtectedRegions {
+    regions: [Option<Range<u32>>; MAX_PROTECTED_REGIONS],
+}
+
+impl ProtectedRegions {
+    /// Adds a region of a device of variant `V`.
+    pub(crate) fn add<V: Variant, E: I2cError>(
+        &mut self,
+        range: Range<u32>,
+    ) -> Result<(), Error<E>> {
+        if range.start > range.end || range.end as usize > V::CAPACITY_BYTES {
+            return Err(Error::OutOfBounds);
+        }
+        let slot = self
+            .regions
+            .iter_mut()
+            .find(|r| r.is_none())
+            .ok_or(Error::TooManyProtectedRegions)?;
+        *slot = Some(range);
+        Ok(())
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.regions = Default::default();
+    }
+
+    /// Checks that `len` bytes at `offset` are in bounds of a device of variant
+    /// `V` and not write-protected.
+    pub(crate) fn check_writable<V: Variant, E: I2cError>(
+        &self,
+        offset: u32,
+        len: usize,
+    ) -> Result<(), Error<E>> {
+        check_bounds::<V, E>(offset, len)?;
+        if self.overlaps(offset, len) {
+            return Err(Error::WriteProtected);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if `len` bytes at `offset` overlap any protected region.
+    fn overlaps(&self, offset: u32, len: usize) -> bool {
+        let end = offset as u64 + len as u64;
+        self.regions
+            .iter()
+            .flatten()
+            .any(|r| (offset as u64) < r.end as u64 && (r.start as u64) < end)
+    }
+}
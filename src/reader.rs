@@ -0,0 +1,106 @@
+//! Sequential reads using the device's internal address latch.
+//!
+//! After every access the F-RAM's address latch points at the byte following
+//! the last one read or written. A [`Reader`] tracks the latch, so reads that
+//! continue where the previous one stopped are issued as current-address reads,
+//! a plain I2C read without the memory address bytes. This saves bus time when
+//! streaming through logs in small chunks.
+
+use core::fmt::Debug;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+
+use crate::address::{check_bounds, slave_address};
+use crate::variant::Variant;
+use crate::{Error, Fm24v10};
+
+impl<I2C, V, WP, E> Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    /// Read sequentially starting at `offset` through a cursor.
+    pub fn reader(&mut self, offset: u32) -> Reader<'_, I2C, V, WP> {
+        Reader {
+            fram: self,
+            position: offset,
+            latch: None,
+        }
+    }
+}
+
+/// Cursor reading the F-RAM sequentially.
+///
+/// The first read after creating the reader, after [`Reader::seek`] to another
+/// position or after a failed read sends the memory address, all others are
+/// current-address reads.
+///
+/// The reader assumes that nothing else accesses the chip while it's alive.
+/// If another driver on a shared bus reads or writes the chip in between, the
+/// latch moves and current-address reads return data from the wrong address.
+/// Create a new reader with [`Fm24v10::reader`] after such accesses.
+pub struct Reader<'a, I2C, V, WP> {
+    fram: &'a mut Fm24v10<I2C, V, WP>,
+    /// Offset of the next byte to read.
+    position: u32,
+    /// Device address latch, if known.
+    latch: Option<u32>,
+}
+
+impl<I2C, V, WP, E> Reader<'_, I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    /// Offset of the next byte to read.
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+
+    /// Move the cursor to `offset`.
+    pub fn seek(&mut self, offset: u32) {
+        self.position = offset;
+    }
+
+    /// Number of bytes between the cursor and the end of the F-RAM.
+    pub fn remaining(&self) -> usize {
+        V::CAPACITY_BYTES.saturating_sub(self.position as usize)
+    }
+
+    /// Read the next `bytes.len()` bytes and advance the cursor.
+    ///
+    /// # Arguments
+    /// * `bytes`: A mutable slice to store the read data.
+    pub async fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error<E>> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        check_bounds::<V, E>(self.position, bytes.len())?;
+
+        // Waking up doesn't touch the latch, but don't rely on it after sleep.
+        if self.fram.sleeping {
+            self.latch = None;
+        }
+        let result = if self.latch == Some(self.position) {
+            self.fram.ensure_awake().await?;
+            let address = slave_address::<V>(self.fram.base_address, self.position);
+            self.fram.i2c.read(address, bytes).await.map_err(Error::I2c)
+        } else {
+            self.fram.read(self.position, bytes).await
+        };
+        if let Err(e) = result {
+            self.latch = None;
+            return Err(e);
+        }
+
+        self.position += bytes.len() as u32;
+        // The latch rolls over to 0 after the last byte of the array.
+        self.latch = Some(self.position % V::CAPACITY_BYTES as u32);
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Typed record storage with serde and postcard.
+//!
+//! A record is stored as a 7-byte header followed by the postcard encoded value:
+//!
+//! | Bytes | Content                                          |
+//! |-------|--------------------------------------------------|
+//! | 0     | Magic byte `0xA5`                                |
+//! | 1-2   | Payload length (little endian)                   |
+//! | 3-6   | CRC-32 of the length and payload (little endian) |
+//! | 7..   | Payload                                          |
+
+use core::fmt::Debug;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::checksum::CRC32;
+use crate::variant::Variant;
+use crate::{Error, Fm24v10};
+
+/// Marks the start of a record.
+const RECORD_MAGIC: u8 = 0xA5;
+
+/// Size of the record header in bytes.
+pub const RECORD_HEADER_BYTES: usize = 7;
+
+fn record_crc(len: [u8; 2], payload: &[u8]) -> u32 {
+    let mut digest = CRC32.digest();
+    digest.update(&len);
+    digest.update(payload);
+    digest.finalize()
+}
+
+impl<I2C, V, WP, E> Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    /// Serialize `value` with postcard and store it as a record at `offset`.
+    ///
+    /// Header and payload are written in a single write. Returns the number of
+    /// bytes written, including the header.
+    ///
+    /// # Arguments
+    /// * `offset`: The memory address offset of the record.
+    /// * `value`: The value to store.
+    /// * `buf`: Scratch buffer, must hold `RECORD_HEADER_BYTES` + the encoded value.
+    pub async fn store<T: Serialize>(
+        &mut self,
+        offset: u32,
+        value: &T,
+        buf: &mut [u8],
+    ) -> Result<usize, Error<E>> {
+        if buf.len() < RECORD_HEADER_BYTES {
+            return Err(Error::Serialization);
+        }
+        let (header, body) = buf.split_at_mut(RECORD_HEADER_BYTES);
+        let payload_len = postcard::to_slice(value, body)
+            .map_err(|_| Error::Serialization)?
+            .len();
+        let len = u16::try_from(payload_len)
+            .map_err(|_| Error::Serialization)?
+            .to_le_bytes();
+
+        header[0] = RECORD_MAGIC;
+        header[1..3].copy_from_slice(&len);
+        header[3..7].copy_from_slice(&record_crc(len, &body[..payload_len]).to_le_bytes());
+
+        let record_len = RECORD_HEADER_BYTES + payload_len;
+        self.write(offset, &buf[..record_len]).await?;
+        Ok(record_len)
+    }
+
+    /// Load and deserialize a record stored with [`Fm24v10::store`].
+    ///
+    /// Returns [`Error::RecordMissing`] if there is no record at `offset`,
+    /// [`Error::RecordCorrupt`] if its length or checksum is invalid and
+    /// [`Error::Serialization`] if `buf` is too small for the payload.
+    ///
+    /// # Arguments
+    /// * `offset`: The memory address offset of the record.
+    /// * `buf`: Scratch buffer, must hold the encoded value.
+    pub async fn load<T: DeserializeOwned>(
+        &mut self,
+        offset: u32,
+        buf: &mut [u8],
+    ) -> Result<T, Error<E>> {
+        let mut header = [0u8; RECORD_HEADER_BYTES];
+        self.read(offset, &mut header).await?;
+        if header[0] != RECORD_MAGIC {
+            return Err(Error::RecordMissing);
+        }
+
+        let len = [header[1], header[2]];
+        let payload_len = u16::from_le_bytes(len) as usize;
+        let payload_offset = offset as usize + RECORD_HEADER_BYTES;
+        if payload_len > V::CAPACITY_BYTES.saturating_sub(payload_offset) {
+            return Err(Error::RecordCorrupt);
+        }
+        if payload_len > buf.len() {
+            return Err(Error::Serialization);
+        }
+        let payload = &mut buf[..payload_len];
+        self.read(offset + RECORD_HEADER_BYTES as u32, payload)
+            .await?;
+
+        let crc = u32::from_le_bytes([header[3], header[4], header[5], header[6]]);
+        if record_crc(len, payload) != crc {
+            return Err(Error::RecordCorrupt);
+        }
+        postcard::from_bytes(payload).map_err(|_| Error::Serialization)
+    }
+}
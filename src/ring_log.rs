@@ -0,0 +1,354 @@
+//! Persistent append-only ring-buffer log with variable-length entries.
+//!
+//! The region starts with the log state (position of the oldest entry, bytes
+//! in use and number of entries) kept in [`AbSlots`], so it survives power
+//! loss at any point. The rest of the region is a circular data area holding
+//! the entries, each made of a 6-byte header (payload length and CRC-32 of the
+//! payload, both little endian) followed by the payload. Entries wrap around
+//! the end of the data area. When the log is full the oldest entries are
+//! dropped to make room.
+
+use core::fmt::Debug;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+
+use crate::checksum::CRC32;
+use crate::slots::{AbSlots, SLOT_HEADER_BYTES};
+use crate::variant::Variant;
+use crate::{Error, Fm24v10};
+
+/// Size of the persisted log state (head, used bytes, entry count).
+const STATE_BYTES: usize = 12;
+
+/// Size of each of the two state slots.
+const STATE_SLOT_BYTES: usize = SLOT_HEADER_BYTES + STATE_BYTES;
+
+/// Bytes of the region reserved for the log state.
+pub const RING_LOG_STATE_BYTES: usize = 2 * STATE_SLOT_BYTES;
+
+/// Size of an entry header (payload length and CRC-32) in bytes.
+pub const RING_LOG_ENTRY_HEADER_BYTES: usize = 6;
+
+/// Position and extent of the entries in the data area.
+#[derive(Debug, Clone, Copy, Default)]
+struct LogState {
+    /// Position of the oldest entry, relative to the data area.
+    head: u32,
+    /// Number of bytes used by entries.
+    used: u32,
+    /// Number of entries.
+    count: u32,
+}
+
+impl LogState {
+    fn to_bytes(self) -> [u8; STATE_BYTES] {
+        let mut bytes = [0u8; STATE_BYTES];
+        bytes[0..4].copy_from_slice(&self.head.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.used.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.count.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; STATE_BYTES]) -> Self {
+        Self {
+            head: u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            used: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            count: u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
+        }
+    }
+}
+
+/// Circular data area of a ring log.
+#[derive(Debug, Clone, Copy)]
+struct DataArea {
+    offset: u32,
+    len: u32,
+}
+
+impl DataArea {
+    /// Read `bytes` starting at data area position `pos`, wrapping at its end.
+    async fn read<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        pos: u32,
+        bytes: &mut [u8],
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let first = bytes.len().min((self.len - pos) as usize);
+        let (head, tail) = bytes.split_at_mut(first);
+        fram.read(self.offset + pos, head).await?;
+        fram.read(self.offset, tail).await
+    }
+
+    /// Write `data` starting at data area position `pos`, wrapping at its end.
+    async fn write<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        pos: u32,
+        data: &[u8],
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let first = data.len().min((self.len - pos) as usize);
+        let (head, tail) = data.split_at(first);
+        fram.write(self.offset + pos, head).await?;
+        fram.write(self.offset, tail).await
+    }
+
+    /// Advance `pos` by `n` bytes, wrapping at the end.
+    fn advance(&self, pos: u32, n: u32) -> u32 {
+        ((pos as u64 + n as u64) % self.len as u64) as u32
+    }
+
+    /// Read and parse the entry header at `pos`, returning payload length and CRC.
+    async fn entry_header<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        pos: u32,
+    ) -> Result<(usize, u32), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let mut header = [0u8; RING_LOG_ENTRY_HEADER_BYTES];
+        self.read(fram, pos, &mut header).await?;
+        let len = u16::from_le_bytes([header[0], header[1]]) as usize;
+        let crc = u32::from_le_bytes([header[2], header[3], header[4], header[5]]);
+        Ok((len, crc))
+    }
+}
+
+/// Persistent circular log keeping the most recent entries.
+#[derive(Debug)]
+pub struct RingLog {
+    slots: AbSlots,
+    data: DataArea,
+    state: LogState,
+}
+
+impl RingLog {
+    /// Mount the log occupying `len` bytes at `offset`.
+    ///
+    /// A region without a valid log state, e.g. on a fresh chip, is mounted as
+    /// an empty log. Returns [`Error::RecordCorrupt`] if the stored state doesn't
+    /// fit the region.
+    pub async fn mount<I2C, V, WP, E>(
+        fram: &mut Fm24v10<I2C, V, WP>,
+        offset: u32,
+        len: usize,
+    ) -> Result<Self, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        if len <= RING_LOG_STATE_BYTES + RING_LOG_ENTRY_HEADER_BYTES {
+            return Err(Error::OutOfBounds);
+        }
+        fram.check_writable(offset, len)?;
+
+        let mut log = Self {
+            slots: AbSlots::new(offset, offset + STATE_SLOT_BYTES as u32, STATE_SLOT_BYTES),
+            data: DataArea {
+                offset: offset + RING_LOG_STATE_BYTES as u32,
+                len: (len - RING_LOG_STATE_BYTES) as u32,
+            },
+            state: LogState::default(),
+        };
+
+        let mut bytes = [0u8; STATE_BYTES];
+        match log.slots.load(fram, &mut bytes).await {
+            Ok(STATE_BYTES) => {
+                let state = LogState::from_bytes(&bytes);
+                if state.head >= log.data.len
+                    || state.used > log.data.len
+                    || (state.count == 0) != (state.used == 0)
+                {
+                    return Err(Error::RecordCorrupt);
+                }
+                log.state = state;
+            }
+            Ok(_) => return Err(Error::RecordCorrupt),
+            Err(Error::RecordMissing) => log.slots.commit(fram, &bytes).await?,
+            Err(e) => return Err(e),
+        }
+        Ok(log)
+    }
+
+    /// Number of entries in the log.
+    pub fn len(&self) -> usize {
+        self.state.count as usize
+    }
+
+    /// Returns `true` if the log holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.state.count == 0
+    }
+
+    /// Largest payload a single entry can hold.
+    pub fn max_entry_len(&self) -> usize {
+        (self.data.len as usize - RING_LOG_ENTRY_HEADER_BYTES).min(u16::MAX as usize)
+    }
+
+    /// Append an entry, dropping the oldest entries if the log is full.
+    ///
+    /// Dropping is persisted before the entry is written, so if power fails in
+    /// between, the oldest entries are gone without the new one being added.
+    ///
+    /// Returns [`Error::RecordCorrupt`] if the header of an entry to drop is
+    /// inconsistent with the log state.
+    pub async fn append<I2C, V, WP, E>(
+        &mut self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        data: &[u8],
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        if data.len() > self.max_entry_len() {
+            return Err(Error::OutOfBounds);
+        }
+        let entry_len = (RING_LOG_ENTRY_HEADER_BYTES + data.len()) as u32;
+
+        // Drop the oldest entries and persist that before overwriting them, so
+        // the state never refers to partially overwritten entries.
+        let mut state = self.state;
+        while self.data.len - state.used < entry_len {
+            let (len, _) = self.data.entry_header(fram, state.head).await?;
+            let dropped = (RING_LOG_ENTRY_HEADER_BYTES + len) as u32;
+            // Entry headers aren't covered by the state checksum.
+            if state.count == 0 || dropped > state.used {
+                return Err(Error::RecordCorrupt);
+            }
+            state.head = self.data.advance(state.head, dropped);
+            state.used -= dropped;
+            state.count -= 1;
+        }
+        if state.count != self.state.count {
+            self.commit(fram, state).await?;
+        }
+
+        let mut header = [0u8; RING_LOG_ENTRY_HEADER_BYTES];
+        header[0..2].copy_from_slice(&(data.len() as u16).to_le_bytes());
+        header[2..6].copy_from_slice(&CRC32.checksum(data).to_le_bytes());
+        let tail = self.data.advance(state.head, state.used);
+        self.data.write(fram, tail, &header).await?;
+        self.data
+            .write(
+                fram,
+                self.data.advance(tail, RING_LOG_ENTRY_HEADER_BYTES as u32),
+                data,
+            )
+            .await?;
+
+        state.used += entry_len;
+        state.count += 1;
+        self.commit(fram, state).await
+    }
+
+    /// Remove all entries.
+    pub async fn clear<I2C, V, WP, E>(
+        &mut self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        self.commit(fram, LogState::default()).await
+    }
+
+    /// Iterate over the entries from oldest to newest.
+    pub fn iter(&self) -> RingLogIter {
+        RingLogIter {
+            data: self.data,
+            pos: self.state.head,
+            remaining: self.state.count,
+        }
+    }
+
+    async fn commit<I2C, V, WP, E>(
+        &mut self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        state: LogState,
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        self.slots.commit(fram, &state.to_bytes()).await?;
+        self.state = state;
+        Ok(())
+    }
+}
+
+/// Cursor over the entries of a [`RingLog`], from oldest to newest.
+///
+/// Reflects the log at the time [`RingLog::iter`] was called.
+#[derive(Debug, Clone)]
+pub struct RingLogIter {
+    data: DataArea,
+    pos: u32,
+    remaining: u32,
+}
+
+impl RingLogIter {
+    /// Read the next entry into `buf`, returning its length, or `None` at the end.
+    ///
+    /// Returns [`Error::RecordCorrupt`] if the entry fails its checksum and
+    /// [`Error::Serialization`] if `buf` is too small for it.
+    pub async fn next<I2C, V, WP, E>(
+        &mut self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        buf: &mut [u8],
+    ) -> Result<Option<usize>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let (len, crc) = self.data.entry_header(fram, self.pos).await?;
+        let payload = buf.get_mut(..len).ok_or(Error::Serialization)?;
+        self.data
+            .read(
+                fram,
+                self.data
+                    .advance(self.pos, RING_LOG_ENTRY_HEADER_BYTES as u32),
+                payload,
+            )
+            .await?;
+        if CRC32.checksum(payload) != crc {
+            return Err(Error::RecordCorrupt);
+        }
+
+        self.pos = self
+            .data
+            .advance(self.pos, (RING_LOG_ENTRY_HEADER_BYTES + len) as u32);
+        self.remaining -= 1;
+        Ok(Some(len))
+    }
+}
@@ -0,0 +1,240 @@
+//! In-memory simulated F-RAM for host-side testing.
+//!
+//! [`SimulatedFm24v10`] implements the async and blocking embedded-hal I2C
+//! traits and decodes bus traffic like the real part: slave address with
+//! page select bits, memory address bytes, the internal address latch used by
+//! current-address reads, address rollover at the end of the array, the
+//! reserved slave IDs for the Device ID and sleep mode, and wake-up from
+//! sleep. Hand it to a driver in place of the I2C bus, optionally wrapped in a
+//! [`fault::FaultyI2c`], and take it back with [`crate::Fm24v10::release`] to
+//! inspect the memory. With the `std` feature, [`image::FileImage`] persists
+//! the contents to a file.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use embedded_hal::i2c::{
+    ErrorKind, ErrorType, I2c as BlockingI2c, NoAcknowledgeSource, Operation, SevenBitAddress,
+};
+use embedded_hal_async::i2c::I2c;
+
+use crate::Address;
+use crate::address::base_address;
+use crate::device_id::{RESERVED_SLAVE_ID, SLEEP_SLAVE_ID};
+use crate::variant::{self, Variant};
+
+pub mod fault;
+#[cfg(feature = "std")]
+pub mod image;
+
+const NAK_ADDRESS: ErrorKind = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+
+/// Simulated I2C F-RAM backed by RAM.
+#[derive(Debug, Clone)]
+pub struct SimulatedFm24v10<V = variant::Fm24v10> {
+    memory: Vec<u8>,
+    /// 7-bit slave address without page select bits.
+    base_address: u8,
+    /// Internal address latch, the address of the next byte accessed.
+    latch: u32,
+    sleeping: bool,
+    /// A write to the reserved slave ID selected this device, so the sleep
+    /// command is accepted in the next transaction.
+    sleep_selected: bool,
+    /// First and last byte written since the last [`Self::take_written`].
+    written: Option<(usize, usize)>,
+    _variant: PhantomData<V>,
+}
+
+impl SimulatedFm24v10 {
+    /// Creates a simulated FM24V10 with all bytes zero.
+    pub fn new(address_pins: Address) -> Self {
+        Self::new_variant(variant::Fm24v10, address_pins)
+    }
+}
+
+impl<V: Variant> SimulatedFm24v10<V> {
+    /// Creates a simulated device of another member of the F-RAM family.
+    pub fn new_variant(_variant: V, address_pins: Address) -> Self {
+        Self {
+            memory: vec![0; V::CAPACITY_BYTES],
+            base_address: base_address::<V>(address_pins),
+            latch: 0,
+            sleeping: false,
+            sleep_selected: false,
+            written: None,
+            _variant: PhantomData,
+        }
+    }
+
+    /// The memory array.
+    pub fn memory(&self) -> &[u8] {
+        &self.memory
+    }
+
+    /// The memory array, e.g. to preload contents.
+    pub fn memory_mut(&mut self) -> &mut [u8] {
+        &mut self.memory
+    }
+
+    /// Put the simulated device into sleep mode.
+    ///
+    /// Over the bus, the simulator enters sleep mode on a write of this
+    /// device's slave address to the reserved slave ID 0xF8, followed by the
+    /// sleep command 0x86 in the next transaction. That is the sequence
+    /// `Fm24v10::sleep` sends, with a stop instead of the datasheet's repeated
+    /// start.
+    pub fn enter_sleep(&mut self) {
+        self.sleeping = true;
+    }
+
+    /// Returns `true` while the simulated device is in sleep mode.
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    /// Returns the range of bytes written over the bus since the last call.
+    #[cfg(feature = "std")]
+    fn take_written(&mut self) -> Option<core::ops::Range<usize>> {
+        self.written.take().map(|(first, last)| first..last + 1)
+    }
+
+    /// Mask of the slave address bits used for page select.
+    fn page_select_mask() -> u8 {
+        (1u8 << V::PAGE_SELECT_BITS) - 1
+    }
+
+    /// Returns `true` if `address` selects this device, for any page.
+    fn is_own_address(&self, address: u8) -> bool {
+        address & !Self::page_select_mask() == self.base_address
+    }
+
+    /// Process one I2C transaction the way the real device would.
+    fn process(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+        if self.sleeping {
+            // Addressing the device wakes it up, but it doesn't acknowledge.
+            if self.is_own_address(address) {
+                self.sleeping = false;
+            }
+            return Err(NAK_ADDRESS);
+        }
+
+        let sleep_selected = core::mem::take(&mut self.sleep_selected);
+        match address {
+            RESERVED_SLAVE_ID => self.reserved(operations),
+            SLEEP_SLAVE_ID if sleep_selected => {
+                self.sleeping = true;
+                Ok(())
+            }
+            _ if self.is_own_address(address) => {
+                self.memory_access(address, operations);
+                Ok(())
+            }
+            _ => Err(NAK_ADDRESS),
+        }
+    }
+
+    /// Handles a transaction to the reserved slave ID.
+    fn reserved(&mut self, operations: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+        let Some(Operation::Write([device, ..])) = operations.first() else {
+            return Err(NAK_ADDRESS);
+        };
+        if !self.is_own_address(device >> 1) {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data));
+        }
+
+        // Without a read, the device waits for the sleep command.
+        if operations.len() == 1 {
+            self.sleep_selected = true;
+            return Ok(());
+        }
+
+        let id = V::DEVICE_ID.map_or([0; 3], |id| id.to_bytes());
+        let mut reads = 0;
+        for operation in operations.iter_mut() {
+            if let Operation::Read(buf) = operation {
+                for byte in buf.iter_mut() {
+                    *byte = id[reads % id.len()];
+                    reads += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles a memory read or write transaction.
+    fn memory_access(&mut self, address: u8, operations: &mut [Operation<'_>]) {
+        let page = (address & Self::page_select_mask()) as u32;
+        let mut address_bytes = 0;
+        let mut memory_address = page;
+        let mut previous_was_read = true;
+
+        for operation in operations.iter_mut() {
+            match operation {
+                Operation::Write(data) => {
+                    // A write after a read (or at the start) begins with the
+                    // memory address bytes.
+                    if previous_was_read {
+                        address_bytes = 0;
+                        memory_address = page;
+                    }
+                    previous_was_read = false;
+                    for &byte in data.iter() {
+                        if address_bytes < V::MEMORY_ADDRESS_BYTES {
+                            memory_address = (memory_address << 8) | byte as u32;
+                            address_bytes += 1;
+                            if address_bytes == V::MEMORY_ADDRESS_BYTES {
+                                self.latch = memory_address % V::CAPACITY_BYTES as u32;
+                            }
+                            continue;
+                        }
+                        let index = self.latch as usize;
+                        self.memory[index] = byte;
+                        self.written = Some(match self.written {
+                            Some((first, last)) => (first.min(index), last.max(index)),
+                            None => (index, index),
+                        });
+                        self.advance();
+                    }
+                }
+                Operation::Read(buf) => {
+                    previous_was_read = true;
+                    for byte in buf.iter_mut() {
+                        *byte = self.memory[self.latch as usize];
+                        self.advance();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Increments the address latch, rolling over at the end of the array.
+    fn advance(&mut self) {
+        self.latch = (self.latch + 1) % V::CAPACITY_BYTES as u32;
+    }
+}
+
+impl<V> ErrorType for SimulatedFm24v10<V> {
+    type Error = ErrorKind;
+}
+
+impl<V: Variant> I2c<SevenBitAddress> for SimulatedFm24v10<V> {
+    async fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.process(address, operations)
+    }
+}
+
+impl<V: Variant> BlockingI2c<SevenBitAddress> for SimulatedFm24v10<V> {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.process(address, operations)
+    }
+}
@@ -0,0 +1,194 @@
+//! Fault-injection wrapper for I2C buses.
+//!
+//! [`FaultyI2c`] forwards transactions to a wrapped bus, typically a
+//! [`SimulatedFm24v10`](super::SimulatedFm24v10), and fails scheduled ones in
+//! the ways real hardware does: a NAKed slave address, a write cut short by
+//! power loss, or bit errors on read data. This allows exercising storage code
+//! built on [`Fm24v10`](crate::Fm24v10) against brown-outs and bus glitches.
+
+use alloc::vec::Vec;
+
+use embedded_hal::i2c::{
+    Error as _, ErrorKind, ErrorType, I2c as BlockingI2c, NoAcknowledgeSource, Operation,
+    SevenBitAddress,
+};
+use embedded_hal_async::i2c::I2c;
+
+/// A failure injected into a single transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// The slave address isn't acknowledged, nothing reaches the wrapped bus.
+    Nak,
+    /// Only the first `n` written bytes, memory address bytes included, reach
+    /// the wrapped bus before the transaction fails, as on power loss during
+    /// a write. Operations after the cut are dropped.
+    TruncateWrite(usize),
+    /// The bits set in `mask` are flipped in read byte `index` (counted over
+    /// all read operations of the transaction) after the transaction completed.
+    FlipRead {
+        /// Index of the read byte to corrupt.
+        index: usize,
+        /// Bits to flip.
+        mask: u8,
+    },
+}
+
+/// I2C bus wrapper injecting scheduled faults.
+///
+/// Transactions are numbered from 0 in the order they are issued. Faults are
+/// scheduled for a transaction number and removed once applied.
+#[derive(Debug)]
+pub struct FaultyI2c<I2C> {
+    inner: I2C,
+    schedule: Vec<(usize, Fault)>,
+    transactions: usize,
+}
+
+impl<I2C> FaultyI2c<I2C> {
+    /// Wraps `inner` without any scheduled faults.
+    pub fn new(inner: I2C) -> Self {
+        Self {
+            inner,
+            schedule: Vec::new(),
+            transactions: 0,
+        }
+    }
+
+    /// Schedule `fault` for transaction number `transaction`.
+    pub fn inject(&mut self, transaction: usize, fault: Fault) {
+        self.schedule.push((transaction, fault));
+    }
+
+    /// Schedule `fault` for the transaction `after` transactions from now,
+    /// `0` being the next one.
+    pub fn inject_after(&mut self, after: usize, fault: Fault) {
+        self.inject(self.transactions + after, fault);
+    }
+
+    /// Remove all scheduled faults.
+    pub fn clear(&mut self) {
+        self.schedule.clear();
+    }
+
+    /// Number of transactions issued so far.
+    pub fn transactions(&self) -> usize {
+        self.transactions
+    }
+
+    /// The wrapped bus.
+    pub fn inner(&self) -> &I2C {
+        &self.inner
+    }
+
+    /// The wrapped bus, mutably.
+    pub fn inner_mut(&mut self) -> &mut I2C {
+        &mut self.inner
+    }
+
+    /// Releases the wrapped bus.
+    pub fn into_inner(self) -> I2C {
+        self.inner
+    }
+
+    /// Takes the fault scheduled for the current transaction and advances the count.
+    fn next_fault(&mut self) -> Option<Fault> {
+        let transaction = self.transactions;
+        self.transactions += 1;
+        let index = self.schedule.iter().position(|(t, _)| *t == transaction)?;
+        Some(self.schedule.swap_remove(index).1)
+    }
+}
+
+/// Reborrows `operations` up to the first `budget` written bytes.
+fn truncate<'a>(operations: &'a mut [Operation<'_>], mut budget: usize) -> Vec<Operation<'a>> {
+    let mut truncated = Vec::new();
+    for operation in operations.iter_mut() {
+        match operation {
+            Operation::Write(data) => {
+                let n = data.len().min(budget);
+                truncated.push(Operation::Write(&data[..n]));
+                budget -= n;
+                if n < data.len() {
+                    break;
+                }
+            }
+            Operation::Read(buf) => truncated.push(Operation::Read(buf)),
+        }
+    }
+    truncated
+}
+
+/// Flips `mask` in read byte `index` of `operations`.
+fn flip(operations: &mut [Operation<'_>], mut index: usize, mask: u8) {
+    for operation in operations.iter_mut() {
+        if let Operation::Read(buf) = operation {
+            if let Some(byte) = buf.get_mut(index) {
+                *byte ^= mask;
+                return;
+            }
+            index -= buf.len();
+        }
+    }
+}
+
+impl<I2C> ErrorType for FaultyI2c<I2C> {
+    type Error = ErrorKind;
+}
+
+impl<I2C: I2c> I2c<SevenBitAddress> for FaultyI2c<I2C> {
+    async fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        match self.next_fault() {
+            None => self
+                .inner
+                .transaction(address, operations)
+                .await
+                .map_err(|e| e.kind()),
+            Some(Fault::Nak) => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+            Some(Fault::TruncateWrite(n)) => {
+                let mut truncated = truncate(operations, n);
+                let _ = self.inner.transaction(address, &mut truncated).await;
+                Err(ErrorKind::Other)
+            }
+            Some(Fault::FlipRead { index, mask }) => {
+                self.inner
+                    .transaction(address, operations)
+                    .await
+                    .map_err(|e| e.kind())?;
+                flip(operations, index, mask);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<I2C: BlockingI2c> BlockingI2c<SevenBitAddress> for FaultyI2c<I2C> {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        match self.next_fault() {
+            None => self
+                .inner
+                .transaction(address, operations)
+                .map_err(|e| e.kind()),
+            Some(Fault::Nak) => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+            Some(Fault::TruncateWrite(n)) => {
+                let mut truncated = truncate(operations, n);
+                let _ = self.inner.transaction(address, &mut truncated);
+                Err(ErrorKind::Other)
+            }
+            Some(Fault::FlipRead { index, mask }) => {
+                self.inner
+                    .transaction(address, operations)
+                    .map_err(|e| e.kind())?;
+                flip(operations, index, mask);
+                Ok(())
+            }
+        }
+    }
+}
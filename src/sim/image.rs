@@ -0,0 +1,152 @@
+//! File-backed simulated F-RAM.
+//!
+//! [`FileImage`] is a [`SimulatedFm24v10`] whose memory array is persisted to
+//! an image file of exactly [`Variant::CAPACITY_BYTES`] bytes, so contents
+//! survive between runs of a desktop simulator. Bytes written over the bus are
+//! written through to the file at the end of each transaction. Images dumped
+//! from real hardware can be loaded with [`FileImage::import`] and the
+//! contents saved elsewhere with [`FileImage::export`].
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c as BlockingI2c, Operation, SevenBitAddress};
+use embedded_hal_async::i2c::I2c;
+
+use super::SimulatedFm24v10;
+use crate::Address;
+use crate::variant::{self, Variant};
+
+/// Simulated I2C F-RAM backed by an image file.
+#[derive(Debug)]
+pub struct FileImage<V = variant::Fm24v10> {
+    sim: SimulatedFm24v10<V>,
+    file: File,
+}
+
+impl FileImage {
+    /// Opens the FM24V10 image at `path`.
+    ///
+    /// A missing or empty file is created with all bytes zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::ErrorKind::InvalidData`] if the file isn't empty and its
+    /// size doesn't match the capacity.
+    pub fn open(path: impl AsRef<Path>, address_pins: Address) -> io::Result<Self> {
+        Self::open_variant(path, variant::Fm24v10, address_pins)
+    }
+}
+
+impl<V: Variant> FileImage<V> {
+    /// Opens the image of another member of the F-RAM family at `path`.
+    ///
+    /// See [`FileImage::open`].
+    pub fn open_variant(
+        path: impl AsRef<Path>,
+        variant: V,
+        address_pins: Address,
+    ) -> io::Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let mut sim = SimulatedFm24v10::new_variant(variant, address_pins);
+        match file.metadata()?.len() {
+            0 => file.set_len(V::CAPACITY_BYTES as u64)?,
+            len if len == V::CAPACITY_BYTES as u64 => file.read_exact(sim.memory_mut())?,
+            _ => return Err(invalid_size()),
+        }
+        Ok(Self { sim, file })
+    }
+
+    /// Replaces the contents with an image read from `reader` and writes it to
+    /// the image file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::ErrorKind::InvalidData`] if `reader` doesn't yield exactly
+    /// [`Variant::CAPACITY_BYTES`] bytes. The contents are left unchanged then.
+    pub fn import(&mut self, mut reader: impl Read) -> io::Result<()> {
+        let mut image = std::vec::Vec::with_capacity(V::CAPACITY_BYTES);
+        reader
+            .by_ref()
+            .take(V::CAPACITY_BYTES as u64 + 1)
+            .read_to_end(&mut image)?;
+        if image.len() != V::CAPACITY_BYTES {
+            return Err(invalid_size());
+        }
+        self.sim.memory_mut().copy_from_slice(&image);
+        self.persist(0..V::CAPACITY_BYTES)
+    }
+
+    /// Writes the contents to `writer`, e.g. to save a snapshot.
+    pub fn export(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(self.sim.memory())
+    }
+
+    /// The simulated device.
+    pub fn sim(&self) -> &SimulatedFm24v10<V> {
+        &self.sim
+    }
+
+    /// The memory array.
+    pub fn memory(&self) -> &[u8] {
+        self.sim.memory()
+    }
+
+    /// Flushes the image file to disk.
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.file.sync_data()
+    }
+
+    /// Writes `range` of the memory array to the image file.
+    fn persist(&mut self, range: core::ops::Range<usize>) -> io::Result<()> {
+        self.file.seek(SeekFrom::Start(range.start as u64))?;
+        self.file.write_all(&self.sim.memory()[range])
+    }
+
+    /// Writes bytes changed by the last transaction to the image file.
+    fn write_through(&mut self, result: Result<(), ErrorKind>) -> Result<(), ErrorKind> {
+        if let Some(range) = self.sim.take_written() {
+            self.persist(range).map_err(|_| ErrorKind::Other)?;
+        }
+        result
+    }
+}
+
+fn invalid_size() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "image size doesn't match the device capacity",
+    )
+}
+
+impl<V> ErrorType for FileImage<V> {
+    type Error = ErrorKind;
+}
+
+impl<V: Variant> I2c<SevenBitAddress> for FileImage<V> {
+    async fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let result = self.sim.process(address, operations);
+        self.write_through(result)
+    }
+}
+
+impl<V: Variant> BlockingI2c<SevenBitAddress> for FileImage<V> {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let result = self.sim.process(address, operations);
+        self.write_through(result)
+    }
+}
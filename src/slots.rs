@@ -0,0 +1,205 @@
+//! Power-fail-safe A/B double-buffered record slots.
+//!
+//! Two F-RAM regions hold alternating copies of a record. Every slot starts
+//! with a 10-byte header followed by the payload:
+//!
+//! | Bytes | Content                                                |
+//! |-------|--------------------------------------------------------|
+//! | 0-3   | Sequence number (little endian)                        |
+//! | 4-5   | Payload length (little endian)                         |
+//! | 6-9   | CRC-32 of sequence, length and payload (little endian) |
+//! | 10..  | Payload                                                |
+//!
+//! [`AbSlots::commit`] always writes the slot not holding the newest valid
+//! record, so a write interrupted by power loss leaves the previous record
+//! intact and [`AbSlots::load`] falls back to it.
+
+use core::fmt::Debug;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+
+use crate::checksum::{CRC32, update_from_fram};
+use crate::variant::Variant;
+use crate::{Error, Fm24v10};
+
+/// Size of the slot header in bytes.
+pub const SLOT_HEADER_BYTES: usize = 10;
+
+/// Newest valid slot found by a scan.
+#[derive(Debug, Clone, Copy)]
+struct ActiveSlot {
+    offset: u32,
+    sequence: u32,
+    len: usize,
+}
+
+/// A/B double-buffered record over two equally sized F-RAM regions.
+#[derive(Debug)]
+pub struct AbSlots {
+    slots: [u32; 2],
+    slot_bytes: usize,
+    /// Cached result of the last scan, `None` until the slots were scanned.
+    active: Option<Option<ActiveSlot>>,
+}
+
+impl AbSlots {
+    /// Creates A/B slots at the given offsets.
+    ///
+    /// # Arguments
+    /// * `slot_a`: Offset of the first slot.
+    /// * `slot_b`: Offset of the second slot.
+    /// * `slot_bytes`: Size of each slot including the [`SLOT_HEADER_BYTES`] header.
+    ///
+    /// # Panics
+    /// If `slot_bytes` can't hold the header.
+    pub const fn new(slot_a: u32, slot_b: u32, slot_bytes: usize) -> Self {
+        assert!(slot_bytes >= SLOT_HEADER_BYTES);
+        Self {
+            slots: [slot_a, slot_b],
+            slot_bytes,
+            active: None,
+        }
+    }
+
+    /// Largest payload a slot can hold.
+    pub const fn max_payload_len(&self) -> usize {
+        self.slot_bytes - SLOT_HEADER_BYTES
+    }
+
+    /// Load the newest valid record into `buf`, returning its length.
+    ///
+    /// Returns [`Error::RecordMissing`] if neither slot holds a valid record and
+    /// [`Error::Serialization`] if `buf` is too small for it.
+    pub async fn load<I2C, V, WP, E>(
+        &mut self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        buf: &mut [u8],
+    ) -> Result<usize, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let active = self.scan(fram).await?.ok_or(Error::RecordMissing)?;
+        let payload = buf.get_mut(..active.len).ok_or(Error::Serialization)?;
+        fram.read(active.offset + SLOT_HEADER_BYTES as u32, payload)
+            .await?;
+        Ok(active.len)
+    }
+
+    /// Write `data` to the inactive slot, making it the newest record.
+    pub async fn commit<I2C, V, WP, E>(
+        &mut self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        data: &[u8],
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        if data.len() > self.max_payload_len() || data.len() > u16::MAX as usize {
+            return Err(Error::OutOfBounds);
+        }
+
+        let (offset, sequence) = match self.scan(fram).await? {
+            Some(active) if active.offset == self.slots[0] => {
+                (self.slots[1], active.sequence.wrapping_add(1))
+            }
+            Some(active) => (self.slots[0], active.sequence.wrapping_add(1)),
+            None => (self.slots[0], 0),
+        };
+
+        let len = (data.len() as u16).to_le_bytes();
+        let mut digest = CRC32.digest();
+        digest.update(&sequence.to_le_bytes());
+        digest.update(&len);
+        digest.update(data);
+
+        let mut header = [0u8; SLOT_HEADER_BYTES];
+        header[0..4].copy_from_slice(&sequence.to_le_bytes());
+        header[4..6].copy_from_slice(&len);
+        header[6..10].copy_from_slice(&digest.finalize().to_le_bytes());
+
+        // Forget the cached state first, so a failed write forces a rescan.
+        self.active = None;
+        fram.write(offset + SLOT_HEADER_BYTES as u32, data).await?;
+        fram.write(offset, &header).await?;
+        self.active = Some(Some(ActiveSlot {
+            offset,
+            sequence,
+            len: data.len(),
+        }));
+        Ok(())
+    }
+
+    /// Find the newest valid slot, using the cached result if available.
+    async fn scan<I2C, V, WP, E>(
+        &mut self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+    ) -> Result<Option<ActiveSlot>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        if let Some(active) = self.active {
+            return Ok(active);
+        }
+
+        let mut newest: Option<ActiveSlot> = None;
+        for offset in self.slots {
+            let Some(slot) = self.validate(fram, offset).await? else {
+                continue;
+            };
+            // Sequence numbers wrap, compare them by distance.
+            let newer = match newest {
+                Some(n) => (slot.sequence.wrapping_sub(n.sequence) as i32) > 0,
+                None => true,
+            };
+            if newer {
+                newest = Some(slot);
+            }
+        }
+        self.active = Some(newest);
+        Ok(newest)
+    }
+
+    /// Check the slot at `offset`, returning it if its header and checksum are valid.
+    async fn validate<I2C, V, WP, E>(
+        &self,
+        fram: &mut Fm24v10<I2C, V, WP>,
+        offset: u32,
+    ) -> Result<Option<ActiveSlot>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        V: Variant,
+        WP: OutputPin,
+        E: Debug + I2cError,
+    {
+        let mut header = [0u8; SLOT_HEADER_BYTES];
+        fram.read(offset, &mut header).await?;
+        let sequence = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let len = u16::from_le_bytes([header[4], header[5]]) as usize;
+        let crc = u32::from_le_bytes([header[6], header[7], header[8], header[9]]);
+        if len > self.max_payload_len() {
+            return Ok(None);
+        }
+
+        let mut digest = CRC32.digest();
+        digest.update(&header[0..6]);
+        update_from_fram(fram, &mut digest, offset + SLOT_HEADER_BYTES as u32, len).await?;
+        if digest.finalize() != crc {
+            return Ok(None);
+        }
+        Ok(Some(ActiveSlot {
+            offset,
+            sequence,
+            len,
+        }))
+    }
+}
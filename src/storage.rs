@@ -0,0 +1,117 @@
+//! [`embedded_storage_async`] trait implementations for [`Fm24v10`].
+//!
+//! F-RAM is byte-addressable and has no erase cycle, so the NOR flash traits are
+//! implemented with a read, write and erase granularity of a single byte. Erasing
+//! simply fills the range with `0xFF`, the erased state NOR flash users expect.
+
+use core::fmt::Debug;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+use embedded_storage_async::nor_flash::{
+    ErrorType, MultiwriteNorFlash, NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash,
+};
+use embedded_storage_async::{ReadStorage, Storage};
+
+use crate::erase::{ERASED, erase_chunks};
+use crate::variant::Variant;
+use crate::{Error, Fm24v10};
+
+impl<E: Debug + I2cError> NorFlashError for Error<E> {
+    fn kind(&self) -> NorFlashErrorKind {
+        match self {
+            Error::OutOfBounds => NorFlashErrorKind::OutOfBounds,
+            _ => NorFlashErrorKind::Other,
+        }
+    }
+}
+
+impl<I2C, V, WP, E> ErrorType for Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    type Error = Error<E>;
+}
+
+impl<I2C, V, WP, E> ReadNorFlash for Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    const READ_SIZE: usize = 1;
+
+    async fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        Fm24v10::read(self, offset, bytes).await
+    }
+
+    fn capacity(&self) -> usize {
+        V::CAPACITY_BYTES
+    }
+}
+
+impl<I2C, V, WP, E> NorFlash for Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    const WRITE_SIZE: usize = 1;
+    const ERASE_SIZE: usize = 1;
+
+    async fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        for (offset, len) in erase_chunks(from, to, V::CAPACITY_BYTES)? {
+            Fm24v10::write(self, offset, &ERASED[..len]).await?;
+        }
+        Ok(())
+    }
+
+    async fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        Fm24v10::write(self, offset, bytes).await
+    }
+}
+
+/// F-RAM cells can be rewritten any number of times without an erase.
+impl<I2C, V, WP, E> MultiwriteNorFlash for Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+}
+
+impl<I2C, V, WP, E> ReadStorage for Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    type Error = Error<E>;
+
+    async fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        Fm24v10::read(self, offset, bytes).await
+    }
+
+    fn capacity(&self) -> usize {
+        V::CAPACITY_BYTES
+    }
+}
+
+impl<I2C, V, WP, E> Storage for Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    async fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        Fm24v10::write(self, offset, bytes).await
+    }
+}
@@ -0,0 +1,211 @@
+//! Typed access to integers and floats stored in the F-RAM.
+//!
+//! Values are stored as their raw bytes in the given [`Endian`] byte order,
+//! without any framing. Arrays are converted through a small stack buffer, so
+//! bulk numeric data doesn't need an intermediate byte buffer.
+
+use core::fmt::Debug;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::i2c::{Error as I2cError, I2c};
+
+use crate::variant::Variant;
+use crate::{Endian, Error, Fm24v10};
+
+/// Size of the stack buffer used to convert arrays.
+const ARRAY_CHUNK_BYTES: usize = 32;
+
+/// Size of the largest [`Scalar`].
+pub(crate) const MAX_SCALAR_BYTES: usize = 8;
+
+mod sealed {
+    /// Keeps [`super::Scalar`] to the primitive numbers, which fit the
+    /// conversion buffers.
+    pub trait Sealed {}
+}
+
+/// A number with a fixed-size byte representation.
+///
+/// The trait is sealed, it's implemented for the primitive integers and
+/// floats only.
+pub trait Scalar: sealed::Sealed + Copy {
+    /// Size of the value in bytes.
+    const BYTES: usize;
+
+    /// Decodes a value from the first [`Self::BYTES`] bytes of `bytes`.
+    fn from_bytes(bytes: &[u8], endian: Endian) -> Self;
+
+    /// Encodes the value into the first [`Self::BYTES`] bytes of `bytes`.
+    fn to_bytes(self, bytes: &mut [u8], endian: Endian);
+}
+
+macro_rules! impl_scalar {
+    ($($t:ty),*) => {
+        $(
+            impl sealed::Sealed for $t {}
+
+            impl Scalar for $t {
+                const BYTES: usize = core::mem::size_of::<$t>();
+
+                fn from_bytes(bytes: &[u8], endian: Endian) -> Self {
+                    let mut raw = [0u8; core::mem::size_of::<$t>()];
+                    raw.copy_from_slice(&bytes[..Self::BYTES]);
+                    match endian {
+                        Endian::Little => <$t>::from_le_bytes(raw),
+                        Endian::Big => <$t>::from_be_bytes(raw),
+                    }
+                }
+
+                fn to_bytes(self, bytes: &mut [u8], endian: Endian) {
+                    let raw = match endian {
+                        Endian::Little => self.to_le_bytes(),
+                        Endian::Big => self.to_be_bytes(),
+                    };
+                    bytes[..Self::BYTES].copy_from_slice(&raw);
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+/// Generates a typed reader and writer per scalar type.
+macro_rules! accessors {
+    ($($t:ty => $read:ident, $write:ident;)*) => {
+        $(
+            #[doc = concat!("Read the `", stringify!($t), "` stored at `offset` in `endian` byte order.")]
+            pub async fn $read(&mut self, offset: u32, endian: Endian) -> Result<$t, Error<E>> {
+                self.read_scalar(offset, endian).await
+            }
+
+            #[doc = concat!("Write `value` as `", stringify!($t), "` at `offset` in `endian` byte order.")]
+            pub async fn $write(
+                &mut self,
+                offset: u32,
+                value: $t,
+                endian: Endian,
+            ) -> Result<(), Error<E>> {
+                self.write_scalar(offset, value, endian).await
+            }
+        )*
+    };
+}
+
+impl<I2C, V, WP, E> Fm24v10<I2C, V, WP>
+where
+    I2C: I2c<Error = E>,
+    V: Variant,
+    WP: OutputPin,
+    E: Debug + I2cError,
+{
+    /// Read the byte at `offset`.
+    pub async fn read_u8(&mut self, offset: u32) -> Result<u8, Error<E>> {
+        self.read_scalar(offset, Endian::Little).await
+    }
+
+    /// Write the byte `value` at `offset`.
+    pub async fn write_u8(&mut self, offset: u32, value: u8) -> Result<(), Error<E>> {
+        self.write_scalar(offset, value, Endian::Little).await
+    }
+
+    /// Read the `i8` stored at `offset`.
+    pub async fn read_i8(&mut self, offset: u32) -> Result<i8, Error<E>> {
+        self.read_scalar(offset, Endian::Little).await
+    }
+
+    /// Write `value` as `i8` at `offset`.
+    pub async fn write_i8(&mut self, offset: u32, value: i8) -> Result<(), Error<E>> {
+        self.write_scalar(offset, value, Endian::Little).await
+    }
+
+    accessors! {
+        u16 => read_u16, write_u16;
+        u32 => read_u32, write_u32;
+        u64 => read_u64, write_u64;
+        i16 => read_i16, write_i16;
+        i32 => read_i32, write_i32;
+        i64 => read_i64, write_i64;
+        f32 => read_f32, write_f32;
+        f64 => read_f64, write_f64;
+    }
+
+    /// Read the value of type `T` stored at `offset` in `endian` byte order.
+    pub async fn read_scalar<T: Scalar>(
+        &mut self,
+        offset: u32,
+        endian: Endian,
+    ) -> Result<T, Error<E>> {
+        let mut bytes = [0u8; MAX_SCALAR_BYTES];
+        self.read(offset, &mut bytes[..T::BYTES]).await?;
+        Ok(T::from_bytes(&bytes, endian))
+    }
+
+    /// Write `value` at `offset` in `endian` byte order.
+    pub async fn write_scalar<T: Scalar>(
+        &mut self,
+        offset: u32,
+        value: T,
+        endian: Endian,
+    ) -> Result<(), Error<E>> {
+        let mut bytes = [0u8; MAX_SCALAR_BYTES];
+        value.to_bytes(&mut bytes, endian);
+        self.write(offset, &bytes[..T::BYTES]).await
+    }
+
+    /// Read consecutive values stored at `offset` in `endian` byte order.
+    ///
+    /// # Arguments
+    /// * `offset`: The memory address offset of the first value.
+    /// * `values`: A mutable slice to store the read values.
+    /// * `endian`: The byte order the values are stored in.
+    pub async fn read_array<T: Scalar>(
+        &mut self,
+        mut offset: u32,
+        values: &mut [T],
+        endian: Endian,
+    ) -> Result<(), Error<E>> {
+        let mut bytes = [0u8; ARRAY_CHUNK_BYTES];
+        for chunk in values.chunks_mut(ARRAY_CHUNK_BYTES / T::BYTES) {
+            let len = chunk.len() * T::BYTES;
+            self.read(offset, &mut bytes[..len]).await?;
+            for (value, raw) in chunk.iter_mut().zip(bytes.chunks(T::BYTES)) {
+                *value = T::from_bytes(raw, endian);
+            }
+            offset += len as u32;
+        }
+        Ok(())
+    }
+
+    /// Write consecutive values at `offset` in `endian` byte order.
+    ///
+    /// Nothing is written if any of the values would be out of bounds or
+    /// write-protected.
+    ///
+    /// # Arguments
+    /// * `offset`: The memory address offset of the first value.
+    /// * `values`: The values to write.
+    /// * `endian`: The byte order to store the values in.
+    pub async fn write_array<T: Scalar>(
+        &mut self,
+        mut offset: u32,
+        values: &[T],
+        endian: Endian,
+    ) -> Result<(), Error<E>> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        self.check_writable(offset, values.len() * T::BYTES)?;
+
+        let mut bytes = [0u8; ARRAY_CHUNK_BYTES];
+        for chunk in values.chunks(ARRAY_CHUNK_BYTES / T::BYTES) {
+            let len = chunk.len() * T::BYTES;
+            for (&value, raw) in chunk.iter().zip(bytes.chunks_mut(T::BYTES)) {
+                value.to_bytes(raw, endian);
+            }
+            self.write(offset, &bytes[..len]).await?;
+            offset += len as u32;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+//! Device variants of the Cypress/Infineon I2C F-RAM family.
+//!
+//! All parts share the `1010b` device type code and the memory access protocol,
+//! but differ in capacity, in the number of memory address bytes sent after the
+//! slave address, and in how the low three bits of the slave address are split
+//! between hardware address pins and page select (upper memory address) bits.
+
+use crate::device_id::{DeviceId, MANUFACTURER_CYPRESS};
+
+/// Describes the memory organisation and addressing of an I2C F-RAM part.
+///
+/// The low three bits of the 7-bit slave address are shared between the
+/// hardware address pins and the page select bits. Page select bits always
+/// occupy the least significant bits and carry the memory address bits above
+/// the ones sent in the memory address bytes.
+pub trait Variant {
+    /// Capacity of the device in bytes.
+    const CAPACITY_BYTES: usize;
+    /// Number of memory address bytes sent after the slave address byte.
+    const MEMORY_ADDRESS_BYTES: usize;
+    /// Number of slave address bits used as page select, starting at bit 0.
+    const PAGE_SELECT_BITS: u8;
+    /// Mask of the slave address bits driven by hardware address pins
+    /// (bit 2: A2, bit 1: A1, bit 0: A0).
+    const ADDRESS_PINS: u8;
+    /// Expected Device ID, or `None` for parts without a Device ID or whose
+    /// ID isn't known to the driver.
+    const DEVICE_ID: Option<DeviceId>;
+}
+
+/// FM24V10, 1Mbit (128KB). Pins A2/A1, A16 as page select.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fm24v10;
+
+impl Variant for Fm24v10 {
+    const CAPACITY_BYTES: usize = 128 * 1024;
+    const MEMORY_ADDRESS_BYTES: usize = 2;
+    const PAGE_SELECT_BITS: u8 = 1;
+    const ADDRESS_PINS: u8 = 0b110;
+    const DEVICE_ID: Option<DeviceId> = Some(DeviceId::new(MANUFACTURER_CYPRESS, 0x400));
+}
+
+/// FM24V05, 512Kbit (64KB). Pins A2/A1/A0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fm24v05;
+
+impl Variant for Fm24v05 {
+    const CAPACITY_BYTES: usize = 64 * 1024;
+    const MEMORY_ADDRESS_BYTES: usize = 2;
+    const PAGE_SELECT_BITS: u8 = 0;
+    const ADDRESS_PINS: u8 = 0b111;
+    const DEVICE_ID: Option<DeviceId> = Some(DeviceId::new(MANUFACTURER_CYPRESS, 0x300));
+}
+
+/// FM24V02, 256Kbit (32KB). Pins A2/A1/A0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fm24v02;
+
+impl Variant for Fm24v02 {
+    const CAPACITY_BYTES: usize = 32 * 1024;
+    const MEMORY_ADDRESS_BYTES: usize = 2;
+    const PAGE_SELECT_BITS: u8 = 0;
+    const ADDRESS_PINS: u8 = 0b111;
+    const DEVICE_ID: Option<DeviceId> = Some(DeviceId::new(MANUFACTURER_CYPRESS, 0x200));
+}
+
+/// FM24V01, 128Kbit (16KB). Pins A2/A1/A0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fm24v01;
+
+impl Variant for Fm24v01 {
+    const CAPACITY_BYTES: usize = 16 * 1024;
+    const MEMORY_ADDRESS_BYTES: usize = 2;
+    const PAGE_SELECT_BITS: u8 = 0;
+    const ADDRESS_PINS: u8 = 0b111;
+    const DEVICE_ID: Option<DeviceId> = Some(DeviceId::new(MANUFACTURER_CYPRESS, 0x100));
+}
+
+/// FM24W256, 256Kbit (32KB). Pins A2/A1/A0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fm24w256;
+
+impl Variant for Fm24w256 {
+    const CAPACITY_BYTES: usize = 32 * 1024;
+    const MEMORY_ADDRESS_BYTES: usize = 2;
+    const PAGE_SELECT_BITS: u8 = 0;
+    const ADDRESS_PINS: u8 = 0b111;
+    const DEVICE_ID: Option<DeviceId> = None;
+}
+
+/// FM24CL64B, 64Kbit (8KB). Pins A2/A1/A0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fm24cl64b;
+
+impl Variant for Fm24cl64b {
+    const CAPACITY_BYTES: usize = 8 * 1024;
+    const MEMORY_ADDRESS_BYTES: usize = 2;
+    const PAGE_SELECT_BITS: u8 = 0;
+    const ADDRESS_PINS: u8 = 0b111;
+    const DEVICE_ID: Option<DeviceId> = None;
+}
+
+/// FM24CL16B, 16Kbit (2KB). No address pins, A10-A8 as page select.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fm24cl16b;
+
+impl Variant for Fm24cl16b {
+    const CAPACITY_BYTES: usize = 2 * 1024;
+    const MEMORY_ADDRESS_BYTES: usize = 1;
+    const PAGE_SELECT_BITS: u8 = 3;
+    const ADDRESS_PINS: u8 = 0b000;
+    const DEVICE_ID: Option<DeviceId> = None;
+}
+
+/// CY15B104Q, 4Mbit (512KB). Pin A2, A17/A16 as page select.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cy15b104q;
+
+impl Variant for Cy15b104q {
+    const CAPACITY_BYTES: usize = 512 * 1024;
+    const MEMORY_ADDRESS_BYTES: usize = 2;
+    const PAGE_SELECT_BITS: u8 = 2;
+    const ADDRESS_PINS: u8 = 0b100;
+    const DEVICE_ID: Option<DeviceId> = None;
+}
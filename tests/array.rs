@@ -0,0 +1,94 @@
+use core::cell::RefCell;
+
+use embedded_hal::i2c::{
+    ErrorKind, ErrorType, I2c as BlockingI2c, NoAcknowledgeSource, Operation, SevenBitAddress,
+};
+use embedded_hal_async::i2c::I2c;
+use fm24v10::sim::SimulatedFm24v10;
+use fm24v10::variant::Fm24v10 as Fm24v10Variant;
+use fm24v10::{Address, Error, Fm24v10, Fm24v10Array, NoPin};
+
+const SECOND_CHIP: Address = Address {
+    a0: 0,
+    a1: 1,
+    a2: 0,
+};
+
+/// One driver's handle to a bus shared by two simulated chips.
+struct SharedBus<'a>(&'a RefCell<[SimulatedFm24v10; 2]>);
+
+impl ErrorType for SharedBus<'_> {
+    type Error = ErrorKind;
+}
+
+impl I2c<SevenBitAddress> for SharedBus<'_> {
+    async fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        // Every chip sees the slave address, the one it selects answers.
+        let not_selected = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+        for chip in self.0.borrow_mut().iter_mut() {
+            match BlockingI2c::transaction(chip, address, operations) {
+                Err(e) if e == not_selected => {}
+                result => return result,
+            }
+        }
+        Err(not_selected)
+    }
+}
+
+fn chips() -> RefCell<[SimulatedFm24v10; 2]> {
+    RefCell::new([
+        SimulatedFm24v10::new(Address::default()),
+        SimulatedFm24v10::new(SECOND_CHIP),
+    ])
+}
+
+fn array(
+    bus: &RefCell<[SimulatedFm24v10; 2]>,
+) -> Fm24v10Array<SharedBus<'_>, Fm24v10Variant, NoPin, 2> {
+    Fm24v10Array::new([
+        Fm24v10::new(SharedBus(bus), Address::default()),
+        Fm24v10::new(SharedBus(bus), SECOND_CHIP),
+    ])
+}
+
+#[tokio::test]
+async fn operations_are_split_at_the_chip_boundary() {
+    let bus = chips();
+    let mut array = array(&bus);
+
+    array.write(0x1_FFFE, &[1, 2, 3, 4]).await.unwrap();
+    {
+        let chips = bus.borrow();
+        assert_eq!(chips[0].memory()[0x1_FFFE..], [1, 2]);
+        assert_eq!(chips[1].memory()[..2], [3, 4]);
+    }
+
+    let mut bytes = [0; 4];
+    array.read(0x1_FFFE, &mut bytes).await.unwrap();
+    assert_eq!(bytes, [1, 2, 3, 4]);
+}
+
+#[tokio::test]
+async fn end_of_the_array_is_out_of_bounds() {
+    let bus = chips();
+    let mut array = array(&bus);
+    assert_eq!(array.capacity().await.unwrap(), 0x4_0000);
+
+    let mut bytes = [0; 2];
+    assert!(matches!(
+        array.read(0x4_0000, &mut bytes[..1]).await,
+        Err(Error::OutOfBounds)
+    ));
+    assert!(matches!(
+        array.write(0x3_FFFF, &[1, 2]).await,
+        Err(Error::OutOfBounds)
+    ));
+    array.write(0x3_FFFE, &[1, 2]).await.unwrap();
+    array.read(0x3_FFFE, &mut bytes).await.unwrap();
+    assert_eq!(bytes, [1, 2]);
+    assert_eq!(bus.borrow()[1].memory()[0x1_FFFE..], [1, 2]);
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::blocking_fram;
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+use fm24v10::blocking::{Fm24v10, WRITE_CHUNK_BYTES};
+use fm24v10::{Address, Error, ProvisioningToken};
+
+#[test]
+fn write_refuses_protected_regions() {
+    let mut fram = blocking_fram();
+    fram.protect(0x100..0x110).unwrap();
+
+    assert!(matches!(
+        fram.write(0xFC, &[1; 8]),
+        Err(Error::WriteProtected)
+    ));
+    fram.write(0xF8, &[1; 8]).unwrap();
+    fram.write(0x110, &[2; 8]).unwrap();
+
+    let mut bytes = [0; 0x20];
+    fram.read(0xF8, &mut bytes).unwrap();
+    assert_eq!(&bytes[..8], &[1; 8]);
+    assert_eq!(&bytes[8..0x18], &[0; 0x10]);
+    assert_eq!(&bytes[0x18..], &[2; 8]);
+}
+
+#[test]
+fn provisioning_bypasses_protection() {
+    let mut fram = blocking_fram();
+    let token = ProvisioningToken::new();
+    fram.protect(0..4).unwrap();
+
+    fram.write_provisioning(&token, 0, b"cal!").unwrap();
+    let mut bytes = [0; 4];
+    fram.read(0, &mut bytes).unwrap();
+    assert_eq!(&bytes, b"cal!");
+
+    fram.clear_protected_regions(&token);
+    fram.write(0, b"free").unwrap();
+}
+
+#[test]
+fn protect_rejects_invalid_ranges() {
+    let mut fram = blocking_fram();
+    assert!(matches!(fram.protect(0..0x2_0001), Err(Error::OutOfBounds)));
+    for i in 0..4 {
+        fram.protect(i..i + 1).unwrap();
+    }
+    assert!(matches!(
+        fram.protect(8..9),
+        Err(Error::TooManyProtectedRegions)
+    ));
+}
+
+#[test]
+fn write_sends_address_and_data_in_one_write() {
+    let expectations = [Transaction::write(0x50, vec![0x12, 0x34, 1, 2, 3])];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    fram.write(0x1234, &[1, 2, 3]).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn write_is_split_into_chunks() {
+    let data: Vec<u8> = (0..WRITE_CHUNK_BYTES as u8 + 2).collect();
+    let mut first = vec![0xFF, 0xFF];
+    first.extend_from_slice(&data[..WRITE_CHUNK_BYTES]);
+    let mut second = vec![0x00, WRITE_CHUNK_BYTES as u8 - 1];
+    second.extend_from_slice(&data[WRITE_CHUNK_BYTES..]);
+    let expectations = [
+        Transaction::write(0x50, first),
+        Transaction::write(0x51, second),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    fram.write(0xFFFF, &data).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn image_round_trip() {
+    let mut fram = blocking_fram();
+    let image: Vec<u8> = (0..0x2_0000u32).map(|i| (i * 7 + i / 251) as u8).collect();
+
+    for (i, chunk) in image.chunks(256).enumerate() {
+        fram.write((i * 256) as u32, chunk).unwrap();
+    }
+
+    let sim = fram.release();
+    assert!(sim.memory() == image.as_slice());
+}
+
+#[test]
+fn verify_accepts_simulated_fm24v10() {
+    blocking_fram().verify().unwrap();
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::fram;
+use fm24v10::{Checksum, Crc16, Crc32, Error};
+
+#[tokio::test]
+async fn round_trip_with_either_checksum() {
+    let mut fram = fram();
+    let mut bytes = [0; 5];
+
+    fram.write_checked(0x100, b"hello", Crc16).await.unwrap();
+    fram.write_checked(0x200, b"hello", Crc32).await.unwrap();
+
+    fram.read_checked(0x100, &mut bytes, Crc16).await.unwrap();
+    assert_eq!(&bytes, b"hello");
+    fram.read_checked(0x200, &mut bytes, Crc32).await.unwrap();
+    assert_eq!(&bytes, b"hello");
+}
+
+#[tokio::test]
+async fn trailer_sizes() {
+    assert_eq!(Crc16::BYTES, 2);
+    assert_eq!(Crc32::BYTES, 4);
+
+    let mut fram = fram();
+    fram.write(0x100, &[0xAA; 8]).await.unwrap();
+    fram.write_checked(0x100, b"ab", Crc16).await.unwrap();
+
+    let mut raw = [0; 5];
+    fram.read(0x100, &mut raw).await.unwrap();
+    assert_eq!(raw[4], 0xAA);
+}
+
+#[tokio::test]
+async fn corrupted_data_is_a_mismatch() {
+    let mut fram = fram();
+    let mut bytes = [0; 5];
+
+    fram.write_checked(0x100, b"hello", Crc32).await.unwrap();
+    fram.write(0x101, b"a").await.unwrap();
+
+    assert!(matches!(
+        fram.read_checked(0x100, &mut bytes, Crc32).await,
+        Err(Error::ChecksumMismatch)
+    ));
+}
+
+#[tokio::test]
+async fn wrong_checksum_is_a_mismatch() {
+    let mut fram = fram();
+    let mut bytes = [0; 5];
+
+    fram.write_checked(0x100, b"hello", Crc16).await.unwrap();
+
+    assert!(matches!(
+        fram.read_checked(0x100, &mut bytes, Crc32).await,
+        Err(Error::ChecksumMismatch)
+    ));
+}
+
+#[tokio::test]
+async fn protected_trailer_writes_nothing() {
+    let mut fram = fram();
+    fram.protect(0x105..0x110).unwrap();
+
+    assert!(matches!(
+        fram.write_checked(0x100, b"hello", Crc32).await,
+        Err(Error::WriteProtected)
+    ));
+
+    let mut raw = [0xFF; 5];
+    fram.read(0x100, &mut raw).await.unwrap();
+    assert_eq!(raw, [0; 5]);
+}
+
+#[tokio::test]
+async fn trailer_out_of_bounds() {
+    let mut fram = fram();
+    let capacity = fram.capacity().await.unwrap() as u32;
+
+    assert!(matches!(
+        fram.write_checked(capacity - 6, b"hello", Crc16).await,
+        Err(Error::OutOfBounds)
+    ));
+    fram.write_checked(capacity - 7, b"hello", Crc16)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,41 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use fm24v10::sim::SimulatedFm24v10;
+use fm24v10::sim::fault::FaultyI2c;
+use fm24v10::{Address, Fm24v10, blocking};
+
+/// Driver on a fault-injecting bus to a simulated FM24V10.
+pub type FaultyFram = Fm24v10<FaultyI2c<SimulatedFm24v10>>;
+
+/// Driver for a blank simulated FM24V10.
+pub fn fram() -> Fm24v10<SimulatedFm24v10> {
+    Fm24v10::new(
+        SimulatedFm24v10::new(Address::default()),
+        Address::default(),
+    )
+}
+
+/// Blocking driver for a blank simulated FM24V10.
+pub fn blocking_fram() -> blocking::Fm24v10<SimulatedFm24v10> {
+    blocking::Fm24v10::new(
+        SimulatedFm24v10::new(Address::default()),
+        Address::default(),
+    )
+}
+
+/// Driver for a blank simulated FM24V10 behind a [`FaultyI2c`].
+pub fn faulty_fram() -> FaultyFram {
+    Fm24v10::new(
+        FaultyI2c::new(SimulatedFm24v10::new(Address::default())),
+        Address::default(),
+    )
+}
+
+/// Takes the bus back from the driver, like a reset would.
+pub fn reset(fram: FaultyFram, f: impl FnOnce(&mut FaultyI2c<SimulatedFm24v10>)) -> FaultyFram {
+    let mut i2c = fram.release();
+    f(&mut i2c);
+    Fm24v10::new(i2c, Address::default())
+}
@@ -0,0 +1,259 @@
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+use fm24v10::sim::SimulatedFm24v10;
+use fm24v10::variant::Fm24cl64b;
+use fm24v10::{
+    Address, DeviceId, Error, Fm24v10, MANUFACTURER_CYPRESS, MAX_BUFFERS_PER_TRANSACTION,
+};
+
+const NAK: ErrorKind = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+
+#[tokio::test]
+async fn write_sends_address_and_data_in_one_transaction() {
+    let expectations = [
+        Transaction::transaction_start(0x50),
+        Transaction::write(0x50, vec![0x12, 0x34]),
+        Transaction::write(0x50, vec![1, 2, 3]),
+        Transaction::transaction_end(0x50),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    fram.write(0x1234, &[1, 2, 3]).await.unwrap();
+    i2c.done();
+}
+
+#[tokio::test]
+async fn write_sets_page_select_bit() {
+    let data = vec![0xAB; 300];
+    let expectations = [
+        Transaction::transaction_start(0x51),
+        Transaction::write(0x51, vec![0x00, 0x10]),
+        Transaction::write(0x51, data.clone()),
+        Transaction::transaction_end(0x51),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    fram.write(0x1_0010, &data).await.unwrap();
+    i2c.done();
+}
+
+#[tokio::test]
+async fn read_sends_address_then_reads() {
+    let expectations = [Transaction::write_read(0x50, vec![0x00, 0x10], vec![4, 5])];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    let mut bytes = [0; 2];
+    fram.read(0x10, &mut bytes).await.unwrap();
+    assert_eq!(bytes, [4, 5]);
+    i2c.done();
+}
+
+#[tokio::test]
+async fn empty_write_sends_nothing() {
+    let mut i2c = Mock::new(&[]);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    fram.write(0, &[]).await.unwrap();
+    i2c.done();
+}
+
+#[tokio::test]
+async fn write_vectored_sends_buffers_as_write_operations() {
+    let expectations = [
+        Transaction::transaction_start(0x50),
+        Transaction::write(0x50, vec![0x00, 0x20]),
+        Transaction::write(0x50, vec![1, 2]),
+        Transaction::write(0x50, vec![3]),
+        Transaction::transaction_end(0x50),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    fram.write_vectored(0x20, &[&[1, 2], &[], &[3]])
+        .await
+        .unwrap();
+    i2c.done();
+}
+
+#[tokio::test]
+async fn read_vectored_sends_buffers_as_read_operations() {
+    let expectations = [
+        Transaction::transaction_start(0x50),
+        Transaction::write(0x50, vec![0x00, 0x20]),
+        Transaction::read(0x50, vec![1, 2]),
+        Transaction::read(0x50, vec![3]),
+        Transaction::transaction_end(0x50),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    let (mut a, mut b) = ([0; 2], [0; 1]);
+    fram.read_vectored(0x20, &mut [&mut a, &mut [], &mut b])
+        .await
+        .unwrap();
+    assert_eq!((a, b), ([1, 2], [3]));
+    i2c.done();
+}
+
+/// Expectations for one transaction of one-byte buffers, the first being `first`.
+fn one_byte_operations(
+    address: u8,
+    memory_address: [u8; 2],
+    first: u8,
+    count: u8,
+    read: bool,
+) -> Vec<Transaction> {
+    let mut expectations = vec![
+        Transaction::transaction_start(address),
+        Transaction::write(address, memory_address.to_vec()),
+    ];
+    for byte in first..first + count {
+        expectations.push(if read {
+            Transaction::read(address, vec![byte])
+        } else {
+            Transaction::write(address, vec![byte])
+        });
+    }
+    expectations.push(Transaction::transaction_end(address));
+    expectations
+}
+
+#[tokio::test]
+async fn many_buffers_are_split_into_transactions() {
+    assert_eq!(MAX_BUFFERS_PER_TRANSACTION, 8);
+    // The second transaction starts on the upper page.
+    let transactions = |read| {
+        [
+            one_byte_operations(0x50, [0xFF, 0xFC], 0, 8, read),
+            one_byte_operations(0x51, [0x00, 0x04], 8, 2, read),
+        ]
+        .concat()
+    };
+    let data: Vec<[u8; 1]> = (0..10).map(|byte| [byte]).collect();
+
+    let mut i2c = Mock::new(&transactions(false));
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+    // Empty buffers don't count towards the limit.
+    let mut buffers: Vec<&[u8]> = data.iter().map(|buf| &buf[..]).collect();
+    buffers.insert(3, &[]);
+    fram.write_vectored(0xFFFC, &buffers).await.unwrap();
+    i2c.done();
+
+    let mut i2c = Mock::new(&transactions(true));
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+    let mut read = [[0xFF; 1]; 10];
+    let mut buffers: Vec<&mut [u8]> = read.iter_mut().map(|buf| &mut buf[..]).collect();
+    fram.read_vectored(0xFFFC, &mut buffers).await.unwrap();
+    assert_eq!(read[..], data[..]);
+    i2c.done();
+}
+
+#[tokio::test]
+async fn verify_accepts_simulated_fm24v10() {
+    let sim = SimulatedFm24v10::new(Address::default());
+    let mut fram = Fm24v10::new(sim, Address::default());
+
+    fram.verify().await.unwrap();
+}
+
+#[tokio::test]
+async fn verify_ignores_revision_and_reports_other_densities() {
+    let expectations = [
+        Transaction::write_read(0x7C, vec![0x50 << 1], vec![0x00, 0x44, 0x05]),
+        Transaction::write_read(0x7C, vec![0x50 << 1], vec![0x00, 0x43, 0x00]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    fram.verify().await.unwrap();
+    match fram.verify().await {
+        Err(Error::DeviceIdMismatch(id)) => {
+            assert_eq!(id, DeviceId::new(MANUFACTURER_CYPRESS, 0x300));
+        }
+        other => panic!("unexpected result {other:?}"),
+    }
+    i2c.done();
+}
+
+#[tokio::test]
+async fn verify_is_unsupported_without_known_device_id() {
+    let mut i2c = Mock::new(&[]);
+    let mut fram = Fm24v10::new_variant(i2c.clone(), Fm24cl64b, Address::default());
+
+    assert!(matches!(fram.verify().await, Err(Error::Unsupported)));
+    i2c.done();
+}
+
+#[tokio::test]
+async fn sleep_not_acknowledged_leaves_device_awake() {
+    let expectations = [
+        Transaction::write(0x7C, vec![0x50 << 1]),
+        Transaction::write(0x43, vec![]).with_error(NAK),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    assert!(matches!(
+        fram.sleep().await,
+        Err(Error::SleepNotAcknowledged)
+    ));
+    assert!(!fram.is_sleeping());
+    i2c.done();
+}
+
+#[tokio::test]
+async fn wake_polls_until_acknowledged() {
+    let expectations = [
+        Transaction::write(0x50, vec![]).with_error(NAK),
+        Transaction::write(0x50, vec![]).with_error(NAK),
+        Transaction::write(0x50, vec![]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    fram.wake().await.unwrap();
+    i2c.done();
+}
+
+#[tokio::test]
+async fn wake_reports_bus_errors() {
+    let expectations = [Transaction::write(0x50, vec![]).with_error(ErrorKind::Bus)];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    assert!(matches!(fram.wake().await, Err(Error::I2c(ErrorKind::Bus))));
+    i2c.done();
+}
+
+#[tokio::test]
+async fn read_wakes_from_simulated_sleep() {
+    let mut sim = SimulatedFm24v10::new(Address::default());
+    sim.memory_mut()[3] = 0x5A;
+    let mut fram = Fm24v10::new(sim, Address::default());
+
+    fram.sleep().await.unwrap();
+    assert!(fram.is_sleeping());
+
+    let mut byte = [0];
+    fram.read(3, &mut byte).await.unwrap();
+    assert_eq!(byte, [0x5A]);
+    assert!(!fram.is_sleeping());
+    assert!(!fram.release().is_sleeping());
+}
+
+#[tokio::test]
+async fn wake_from_simulated_sleep() {
+    let mut sim = SimulatedFm24v10::new(Address::default());
+    sim.memory_mut()[3] = 0x5A;
+    sim.enter_sleep();
+    let mut fram = Fm24v10::new(sim, Address::default());
+
+    let mut byte = [0];
+    assert!(fram.read(3, &mut byte).await.is_err());
+    fram.wake().await.unwrap();
+    fram.read(3, &mut byte).await.unwrap();
+    assert_eq!(byte, [0x5A]);
+}
@@ -0,0 +1,92 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use fm24v10::sim::image::FileImage;
+use fm24v10::{Address, Fm24v10};
+
+const CAPACITY: usize = 0x2_0000;
+
+/// Image file in the temp directory, removed when dropped.
+struct TempImage(PathBuf);
+
+impl TempImage {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("fm24v10-{}-{name}.img", std::process::id()));
+        let _ = fs::remove_file(&path);
+        Self(path)
+    }
+
+    fn open(&self) -> std::io::Result<FileImage> {
+        FileImage::open(&self.0, Address::default())
+    }
+}
+
+impl Drop for TempImage {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[tokio::test]
+async fn writes_persist_across_open() {
+    let file = TempImage::new("persist");
+    let image = file.open().unwrap();
+    assert_eq!(fs::metadata(&file.0).unwrap().len(), CAPACITY as u64);
+
+    let mut fram = Fm24v10::new(image, Address::default());
+    fram.write(0x1_FFFE, &[1, 2]).await.unwrap();
+    drop(fram);
+
+    let image = file.open().unwrap();
+    assert_eq!(image.memory()[0x1_FFFE..], [1, 2]);
+    let mut fram = Fm24v10::new(image, Address::default());
+    let mut bytes = [0; 2];
+    fram.read(0x1_FFFE, &mut bytes).await.unwrap();
+    assert_eq!(bytes, [1, 2]);
+}
+
+#[test]
+fn import_rejects_wrong_sizes() {
+    let file = TempImage::new("import");
+    let mut image = file.open().unwrap();
+    let mut contents = vec![0; CAPACITY];
+    contents[7] = 7;
+    image.import(&contents[..]).unwrap();
+
+    for len in [0, CAPACITY - 1, CAPACITY + 1] {
+        let err = image.import(&vec![0xFF; len][..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(image.memory(), contents);
+    }
+    drop(image);
+    assert_eq!(file.open().unwrap().memory(), contents);
+}
+
+#[test]
+fn export_round_trip() {
+    let file = TempImage::new("export");
+    let mut image = file.open().unwrap();
+    let contents: Vec<u8> = (0..CAPACITY).map(|i| i as u8).collect();
+    image.import(&contents[..]).unwrap();
+
+    let mut exported = Vec::new();
+    image.export(&mut exported).unwrap();
+    assert_eq!(exported, contents);
+
+    let other = TempImage::new("export-other");
+    let mut copy = other.open().unwrap();
+    copy.import(&exported[..]).unwrap();
+    assert_eq!(copy.memory(), contents);
+}
+
+#[test]
+fn mis_sized_file_is_invalid_data() {
+    let file = TempImage::new("mis-sized");
+    fs::write(&file.0, [0; 16]).unwrap();
+
+    let err = file.open().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    // The file is left alone.
+    assert_eq!(fs::read(&file.0).unwrap(), [0; 16]);
+}
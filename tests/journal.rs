@@ -0,0 +1,172 @@
+mod common;
+
+use common::{FaultyFram, faulty_fram, reset};
+use embedded_hal::i2c::ErrorKind;
+use fm24v10::sim::fault::Fault;
+use fm24v10::{Error, JOURNAL_HEADER_BYTES, Journal, Recovery};
+
+const JOURNAL: u32 = 0x1000;
+const JOURNAL_BYTES: usize = 128;
+const A: u32 = 0x100;
+const B: u32 = 0x300;
+
+async fn mount(fram: &mut FaultyFram) -> (Journal, Recovery) {
+    Journal::mount(fram, JOURNAL, JOURNAL_BYTES).await.unwrap()
+}
+
+async fn read(fram: &mut FaultyFram, offset: u32) -> [u8; 4] {
+    let mut bytes = [0; 4];
+    fram.read(offset, &mut bytes).await.unwrap();
+    bytes
+}
+
+/// Writes `a` and `b` to their targets in one transaction.
+async fn update(
+    fram: &mut FaultyFram,
+    journal: &Journal,
+    a: &[u8],
+    b: &[u8],
+) -> Result<(), Error<ErrorKind>> {
+    let mut transaction = journal.begin(fram);
+    transaction.write(A, a).await?;
+    transaction.write(B, b).await?;
+    transaction.commit().await
+}
+
+/// A fresh F-RAM holding `old` in both targets.
+async fn prepared() -> FaultyFram {
+    let mut fram = faulty_fram();
+    fram.write(A, b"old!").await.unwrap();
+    fram.write(B, b"OLD!").await.unwrap();
+    fram
+}
+
+#[tokio::test]
+async fn commit_applies_all_writes() {
+    let mut fram = prepared().await;
+    let (journal, recovery) = mount(&mut fram).await;
+    assert_eq!(recovery, Recovery::Clean);
+
+    update(&mut fram, &journal, b"new!", b"NEW!").await.unwrap();
+
+    assert_eq!(&read(&mut fram, A).await, b"new!");
+    assert_eq!(&read(&mut fram, B).await, b"NEW!");
+    assert_eq!(mount(&mut fram).await.1, Recovery::Clean);
+}
+
+#[tokio::test]
+async fn dropped_transaction_changes_nothing() {
+    let mut fram = prepared().await;
+    let (journal, _) = mount(&mut fram).await;
+
+    {
+        // Logged, then dropped without committing.
+        let mut transaction = journal.begin(&mut fram);
+        transaction.write(A, b"new!").await.unwrap();
+    }
+
+    assert_eq!(&read(&mut fram, A).await, b"old!");
+    assert_eq!(mount(&mut fram).await.1, Recovery::Clean);
+}
+
+#[tokio::test]
+async fn corrupt_commit_is_rolled_back() {
+    let mut fram = prepared().await;
+    let (journal, _) = mount(&mut fram).await;
+
+    {
+        // Logged, then dropped without committing.
+        let mut transaction = journal.begin(&mut fram);
+        transaction.write(A, b"new!").await.unwrap();
+    }
+    // A commit marker whose checksum doesn't match the log.
+    fram.write(JOURNAL, &[0xC3, 10, 0, 0, 0, 1, 2, 3, 4])
+        .await
+        .unwrap();
+
+    assert_eq!(mount(&mut fram).await.1, Recovery::RolledBack);
+    assert_eq!(&read(&mut fram, A).await, b"old!");
+    assert_eq!(mount(&mut fram).await.1, Recovery::Clean);
+}
+
+#[tokio::test]
+async fn crash_after_commit_marker_replays() {
+    let mut fram = prepared().await;
+    let (journal, _) = mount(&mut fram).await;
+
+    // Power fails after the targets were written, before the journal is
+    // cleared by the last transaction of the update.
+    let (mut fram, start) = transactions(fram);
+    update(&mut fram, &journal, b"mid!", b"MID!").await.unwrap();
+    let (fram, end) = transactions(fram);
+    let mut fram = reset(fram, |i2c| i2c.inject_after(end - start - 1, Fault::Nak));
+    assert!(update(&mut fram, &journal, b"new!", b"NEW!").await.is_err());
+
+    // Undo one target to show the replay writes it again.
+    fram.write(A, b"old!").await.unwrap();
+    assert_eq!(mount(&mut fram).await.1, Recovery::Replayed);
+    assert_eq!(&read(&mut fram, A).await, b"new!");
+    assert_eq!(&read(&mut fram, B).await, b"NEW!");
+}
+
+#[tokio::test]
+async fn power_loss_is_all_or_nothing() {
+    // Number of transactions of an undisturbed update.
+    let mut fram = prepared().await;
+    let (journal, _) = mount(&mut fram).await;
+    let (mut fram, start) = transactions(fram);
+    update(&mut fram, &journal, b"new!", b"NEW!").await.unwrap();
+    let (_, end) = transactions(fram);
+
+    let mut replayed = false;
+    for transaction in 0..end - start {
+        for fault in [Fault::Nak, Fault::TruncateWrite(2), Fault::TruncateWrite(3)] {
+            let mut fram = prepared().await;
+            let (journal, _) = mount(&mut fram).await;
+            let mut fram = reset(fram, |i2c| i2c.inject_after(transaction, fault));
+            let result = update(&mut fram, &journal, b"new!", b"NEW!").await;
+
+            let mut fram = reset(fram, |i2c| i2c.clear());
+            let (_, recovery) = mount(&mut fram).await;
+            let (a, b) = (read(&mut fram, A).await, read(&mut fram, B).await);
+            match (&a, &b) {
+                (b"old!", b"OLD!") => assert!(result.is_err()),
+                (b"new!", b"NEW!") => {}
+                _ => panic!("torn update at {transaction} with {fault:?}: {a:?} {b:?}"),
+            }
+            replayed |= recovery == Recovery::Replayed;
+        }
+    }
+    assert!(replayed);
+}
+
+#[tokio::test]
+async fn full_journal() {
+    let mut fram = faulty_fram();
+    let (journal, _) = mount(&mut fram).await;
+    let mut transaction = journal.begin(&mut fram);
+
+    let data = [0; JOURNAL_BYTES - JOURNAL_HEADER_BYTES];
+    assert!(matches!(
+        transaction.write(A, &data).await,
+        Err(Error::JournalFull)
+    ));
+}
+
+#[tokio::test]
+async fn writes_into_the_journal_are_rejected() {
+    let mut fram = faulty_fram();
+    let (journal, _) = mount(&mut fram).await;
+    let mut transaction = journal.begin(&mut fram);
+
+    assert!(matches!(
+        transaction.write(JOURNAL + 4, b"x").await,
+        Err(Error::OutOfBounds)
+    ));
+}
+
+fn transactions(fram: FaultyFram) -> (FaultyFram, usize) {
+    let mut count = 0;
+    let fram = reset(fram, |i2c| count = i2c.transactions());
+    (fram, count)
+}
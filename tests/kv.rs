@@ -0,0 +1,165 @@
+mod common;
+
+use common::{FaultyFram, faulty_fram, reset};
+use embedded_hal::i2c::ErrorKind;
+use fm24v10::sim::fault::Fault;
+use fm24v10::{Error, KV_MAX_KEY_BYTES, KV_SLOT_HEADER_BYTES, KvStore};
+
+const STORE: KvStore = KvStore::new(0x800, 4 * 32, 32);
+
+async fn fram() -> FaultyFram {
+    let mut fram = faulty_fram();
+    STORE.format(&mut fram).await.unwrap();
+    fram
+}
+
+async fn get(fram: &mut FaultyFram, key: &str) -> Result<Option<Vec<u8>>, Error<ErrorKind>> {
+    let mut buf = [0; 32];
+    Ok(STORE
+        .get(fram, key, &mut buf)
+        .await?
+        .map(|len| buf[..len].to_vec()))
+}
+
+#[tokio::test]
+async fn set_get_overwrite() {
+    let mut fram = fram().await;
+    assert_eq!(get(&mut fram, "volume").await.unwrap(), None);
+
+    STORE.set(&mut fram, "volume", &[7]).await.unwrap();
+    STORE.set(&mut fram, "name", b"atov").await.unwrap();
+    assert_eq!(get(&mut fram, "volume").await.unwrap(), Some(vec![7]));
+
+    STORE.set(&mut fram, "volume", &[8, 9]).await.unwrap();
+    assert_eq!(get(&mut fram, "volume").await.unwrap(), Some(vec![8, 9]));
+    assert_eq!(
+        get(&mut fram, "name").await.unwrap(),
+        Some(b"atov".to_vec())
+    );
+}
+
+#[tokio::test]
+async fn remove_frees_the_slot() {
+    let mut fram = fram().await;
+    STORE.set(&mut fram, "volume", &[7]).await.unwrap();
+
+    assert!(STORE.remove(&mut fram, "volume").await.unwrap());
+    assert!(!STORE.remove(&mut fram, "volume").await.unwrap());
+    assert_eq!(get(&mut fram, "volume").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn iterate_in_slot_order() {
+    let mut fram = fram().await;
+    STORE.set(&mut fram, "a", b"1").await.unwrap();
+    STORE.set(&mut fram, "b", b"22").await.unwrap();
+    STORE.set(&mut fram, "c", b"333").await.unwrap();
+    STORE.remove(&mut fram, "b").await.unwrap();
+
+    let mut iter = STORE.iter();
+    let mut key = [0; KV_MAX_KEY_BYTES];
+    let mut value = [0; 32];
+    let mut entries = Vec::new();
+    while let Some((key_len, value_len)) = iter.next(&mut fram, &mut key, &mut value).await.unwrap()
+    {
+        entries.push((key[..key_len].to_vec(), value[..value_len].to_vec()));
+    }
+
+    assert_eq!(
+        entries,
+        [
+            (b"a".to_vec(), b"1".to_vec()),
+            (b"c".to_vec(), b"333".to_vec())
+        ]
+    );
+}
+
+#[tokio::test]
+async fn store_full() {
+    let mut fram = fram().await;
+    for key in ["a", "b", "c", "d"] {
+        STORE.set(&mut fram, key, &[1]).await.unwrap();
+    }
+
+    assert!(matches!(
+        STORE.set(&mut fram, "e", &[1]).await,
+        Err(Error::StoreFull)
+    ));
+    // Existing keys can still be updated.
+    STORE.set(&mut fram, "d", &[2]).await.unwrap();
+}
+
+#[tokio::test]
+async fn oversized_entries_are_rejected() {
+    let mut fram = fram().await;
+
+    let value = [0; 32 - KV_SLOT_HEADER_BYTES];
+    assert!(matches!(
+        STORE.set(&mut fram, "k", &value).await,
+        Err(Error::OutOfBounds)
+    ));
+    assert!(matches!(
+        STORE.set(&mut fram, "", &[1]).await,
+        Err(Error::OutOfBounds)
+    ));
+}
+
+#[tokio::test]
+async fn small_buffers_are_a_serialization_error() {
+    let mut fram = fram().await;
+    STORE.set(&mut fram, "volume", &[7, 8]).await.unwrap();
+
+    let mut buf = [0; 1];
+    assert!(matches!(
+        STORE.get(&mut fram, "volume", &mut buf).await,
+        Err(Error::Serialization)
+    ));
+    let mut key = [0; 5];
+    assert!(matches!(
+        STORE.iter().next(&mut fram, &mut key, &mut [0; 2]).await,
+        Err(Error::Serialization)
+    ));
+    let mut key = [0; KV_MAX_KEY_BYTES];
+    assert!(matches!(
+        STORE.iter().next(&mut fram, &mut key, &mut buf).await,
+        Err(Error::Serialization)
+    ));
+}
+
+#[tokio::test]
+async fn corrupt_slot_is_reported() {
+    let mut fram = fram().await;
+    STORE.set(&mut fram, "volume", &[7]).await.unwrap();
+
+    fram.write(0x800 + (KV_SLOT_HEADER_BYTES + 6) as u32, &[8])
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        get(&mut fram, "volume").await,
+        Err(Error::RecordCorrupt)
+    ));
+}
+
+#[tokio::test]
+async fn interrupted_update_is_reported() {
+    let mut fram = fram().await;
+    STORE.set(&mut fram, "volume", b"1111").await.unwrap();
+
+    // Count the transactions of an update, the value is written second to last.
+    let mut start = 0;
+    let mut fram = reset(fram, |i2c| start = i2c.transactions());
+    STORE.set(&mut fram, "volume", b"2222").await.unwrap();
+
+    // Power fails after two bytes of the new value (and the memory address).
+    let mut fram = reset(fram, |i2c| {
+        let transactions = i2c.transactions() - start;
+        i2c.inject_after(transactions - 2, Fault::TruncateWrite(4));
+    });
+    assert!(STORE.set(&mut fram, "volume", b"3333").await.is_err());
+
+    assert!(matches!(
+        get(&mut fram, "volume").await,
+        Err(Error::RecordCorrupt)
+    ));
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::fram;
+use fm24v10::{FramLayout, variant};
+
+#[derive(FramLayout)]
+#[fram(offset = 0x100)]
+#[repr(C)]
+struct Config {
+    flags: u8,
+    boot_count: u32,
+    r#type: u16,
+    serial: [u8; 6],
+}
+
+#[derive(FramLayout)]
+#[fram(offset = 0x7E0, variant = variant::Fm24cl16b)]
+#[repr(C)]
+struct Tail {
+    serial: [u8; 32],
+}
+
+#[test]
+fn offsets_follow_repr_c() {
+    assert_eq!(Config::OFFSET, 0x100);
+    assert_eq!(Config::BYTES, 16);
+    assert_eq!(Config::FLAGS.offset(), 0x100);
+    // Padded to the alignment of u32.
+    assert_eq!(Config::BOOT_COUNT.offset(), 0x104);
+    assert_eq!(Config::TYPE.offset(), 0x108);
+    assert_eq!(Config::SERIAL.offset(), 0x10A);
+}
+
+#[test]
+fn layout_may_end_at_the_capacity() {
+    assert_eq!(Tail::OFFSET as usize + Tail::BYTES, 0x800);
+    assert_eq!(Tail::SERIAL.offset(), 0x7E0);
+}
+
+#[tokio::test]
+async fn fields_round_trip() {
+    let mut fram = fram();
+
+    Config::BOOT_COUNT
+        .write(&mut fram, &0x1234_5678)
+        .await
+        .unwrap();
+    Config::TYPE.write(&mut fram, &7).await.unwrap();
+    Config::SERIAL.write(&mut fram, b"atov01").await.unwrap();
+
+    assert_eq!(
+        Config::BOOT_COUNT.read(&mut fram).await.unwrap(),
+        0x1234_5678
+    );
+    assert_eq!(Config::TYPE.read(&mut fram).await.unwrap(), 7);
+    assert_eq!(&Config::SERIAL.read(&mut fram).await.unwrap(), b"atov01");
+    assert_eq!(Config::FLAGS.read(&mut fram).await.unwrap(), 0);
+
+    let sim = fram.release();
+    assert_eq!(&sim.memory()[0x104..0x108], &[0x78, 0x56, 0x34, 0x12]);
+}
@@ -0,0 +1,84 @@
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+use fm24v10::{Address, Endian, Fm24v10};
+
+/// Expectations for writing `data` at the 16-bit memory address `offset`.
+fn write(offset: u16, data: Vec<u8>) -> [Transaction; 4] {
+    [
+        Transaction::transaction_start(0x50),
+        Transaction::write(0x50, offset.to_be_bytes().to_vec()),
+        Transaction::write(0x50, data),
+        Transaction::transaction_end(0x50),
+    ]
+}
+
+fn read(offset: u16, data: Vec<u8>) -> Transaction {
+    Transaction::write_read(0x50, offset.to_be_bytes().to_vec(), data)
+}
+
+#[tokio::test]
+async fn increment_without_carry_writes_one_byte() {
+    let expectations = [
+        vec![read(0x40, vec![5, 0, 0, 0])],
+        write(0x40, vec![6]).to_vec(),
+    ]
+    .concat();
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    assert_eq!(fram.increment_u32(0x40, Endian::Little).await.unwrap(), 6);
+    i2c.done();
+}
+
+#[tokio::test]
+async fn increment_rewrites_bytes_affected_by_the_carry() {
+    let expectations = [
+        vec![read(0x40, vec![0, 0, 1, 0xFF])],
+        write(0x42, vec![2, 0]).to_vec(),
+    ]
+    .concat();
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    assert_eq!(fram.increment_u32(0x40, Endian::Big).await.unwrap(), 0x200);
+    i2c.done();
+}
+
+#[tokio::test]
+async fn unchanged_value_writes_nothing() {
+    let expectations = [
+        read(0x40, vec![0x81]),
+        read(0x40, vec![0x81]),
+        read(0x40, vec![1, 2]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    fram.set_bits(0x40, 0x01).await.unwrap();
+    fram.clear_bits(0x40, 0x02).await.unwrap();
+    fram.update(0x40, |bytes: &mut [u8; 2]| bytes[1] = 2)
+        .await
+        .unwrap();
+    i2c.done();
+}
+
+#[tokio::test]
+async fn update_writes_the_changed_span() {
+    let expectations = [
+        vec![read(0x40, vec![0x81])],
+        write(0x40, vec![0x01]).to_vec(),
+        vec![read(0x40, vec![1, 2, 3, 4])],
+        write(0x41, vec![0, 3, 0]).to_vec(),
+    ]
+    .concat();
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+
+    fram.clear_bits(0x40, 0x80).await.unwrap();
+    fram.update(0x40, |bytes: &mut [u8; 4]| {
+        bytes[1] = 0;
+        bytes[3] = 0;
+    })
+    .await
+    .unwrap();
+    i2c.done();
+}
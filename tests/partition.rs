@@ -0,0 +1,102 @@
+mod common;
+
+use common::fram;
+use embedded_hal::i2c::ErrorKind;
+use embedded_storage_async::nor_flash::{NorFlash, ReadNorFlash};
+use fm24v10::sim::SimulatedFm24v10;
+use fm24v10::{Error, Fm24v10, Partition, PartitionHandle, PartitionTable, variant};
+
+const CONFIG: Partition = Partition::new(0x100, 0x40);
+const LOG: Partition = CONFIG.after(0x80);
+const TABLE: PartitionTable<variant::Fm24v10, 2> = PartitionTable::new([CONFIG, LOG]);
+
+/// A subsystem keeping its partition handle.
+struct Config {
+    partition: PartitionHandle<variant::Fm24v10>,
+}
+
+impl Config {
+    async fn store(
+        &self,
+        fram: &mut Fm24v10<SimulatedFm24v10>,
+        value: u8,
+    ) -> Result<(), Error<ErrorKind>> {
+        self.partition.write(fram, 0, &[value]).await
+    }
+}
+
+#[tokio::test]
+async fn handles_are_zero_based() {
+    let mut fram = fram();
+    let config = Config {
+        partition: TABLE.handle(0),
+    };
+
+    config.store(&mut fram, 7).await.unwrap();
+    TABLE.handle(1).write(&mut fram, 1, b"log").await.unwrap();
+
+    let mut bytes = [0; 4];
+    fram.read(0x100, &mut bytes[..1]).await.unwrap();
+    assert_eq!(bytes[0], 7);
+    fram.read(0x141, &mut bytes[..3]).await.unwrap();
+    assert_eq!(&bytes[..3], b"log");
+    TABLE
+        .handle(1)
+        .read(&mut fram, 0, &mut bytes)
+        .await
+        .unwrap();
+    assert_eq!(&bytes, b"\0log");
+}
+
+#[tokio::test]
+async fn accesses_are_bounded_to_the_partition() {
+    let mut fram = fram();
+    let config = TABLE.handle(0);
+
+    assert_eq!(config.capacity(), 0x40);
+    assert!(matches!(
+        config.write(&mut fram, 0x3F, &[1, 2]).await,
+        Err(Error::OutOfBounds)
+    ));
+    assert!(matches!(
+        config.read(&mut fram, 0x41, &mut []).await,
+        Err(Error::OutOfBounds)
+    ));
+    config.write(&mut fram, 0x3E, &[1, 2]).await.unwrap();
+
+    let mut next = [0];
+    fram.read(LOG.offset(), &mut next).await.unwrap();
+    assert_eq!(next, [0]);
+}
+
+#[tokio::test]
+async fn bound_partition_implements_nor_flash() {
+    let mut fram = fram();
+    let log = TABLE.handle(1);
+    let mut flash = log.bind(&mut fram);
+
+    assert_eq!(ReadNorFlash::capacity(&flash), 0x80);
+    NorFlash::write(&mut flash, 0x10, &[1, 2, 3]).await.unwrap();
+    NorFlash::erase(&mut flash, 0x11, 0x12).await.unwrap();
+    assert!(matches!(
+        NorFlash::erase(&mut flash, 0x70, 0x81).await,
+        Err(Error::OutOfBounds)
+    ));
+
+    let mut bytes = [0; 3];
+    ReadNorFlash::read(&mut flash, 0x10, &mut bytes)
+        .await
+        .unwrap();
+    assert_eq!(bytes, [1, 0xFF, 3]);
+    fram.read(LOG.offset() + 0x10, &mut bytes).await.unwrap();
+    assert_eq!(bytes, [1, 0xFF, 3]);
+}
+
+#[test]
+fn table_lists_partitions() {
+    assert_eq!(TABLE.get(1), LOG);
+    assert_eq!(TABLE.partitions(), &[CONFIG, LOG]);
+    assert_eq!(TABLE.handle(1).partition(), LOG);
+    assert_eq!(LOG.offset(), 0x140);
+    assert_eq!(LOG.end(), 0x1C0);
+}
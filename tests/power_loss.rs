@@ -0,0 +1,238 @@
+//! Sweeps a fault over every transaction of an update of each storage layer.
+//!
+//! Power loss, a NAK or a write cut short at any point must leave the old or
+//! the new contents after a reset. Bit errors on reads can't always be told
+//! apart from corrupt data, they may e.g. make the newest A/B slot look
+//! invalid, so those only have to leave the storage mountable without panics.
+
+mod common;
+
+use common::{FaultyFram, faulty_fram, reset};
+use embedded_hal::i2c::ErrorKind;
+use fm24v10::sim::fault::Fault;
+use fm24v10::{AbSlots, Error, Journal, KvStore, RingLog};
+
+type Result<T> = core::result::Result<T, Error<ErrorKind>>;
+
+/// An update of a storage layer from `OLD` to `NEW` contents.
+trait Scenario {
+    const OLD: &[u8];
+    const NEW: &[u8];
+
+    async fn prepare(fram: &mut FaultyFram);
+    async fn update(fram: &mut FaultyFram) -> Result<()>;
+    /// Mounts the storage after a reset and reads its contents.
+    async fn contents(fram: &mut FaultyFram) -> Result<Vec<u8>>;
+
+    /// Whether `contents` is an acceptable outcome of an interrupted update.
+    fn is_old_or_new(contents: &Result<Vec<u8>>) -> bool {
+        matches!(contents, Ok(c) if c == Self::OLD || c == Self::NEW)
+    }
+}
+
+const POWER_LOSS: [Fault; 8] = [
+    Fault::Nak,
+    Fault::TruncateWrite(0),
+    Fault::TruncateWrite(1),
+    Fault::TruncateWrite(2),
+    Fault::TruncateWrite(3),
+    Fault::TruncateWrite(4),
+    Fault::TruncateWrite(6),
+    Fault::TruncateWrite(9),
+];
+
+fn bit_errors() -> impl Iterator<Item = Fault> {
+    [0, 1, 2, 4, 6, 9]
+        .into_iter()
+        .map(|index| Fault::FlipRead { index, mask: 0x81 })
+}
+
+async fn prepared<S: Scenario>() -> FaultyFram {
+    let mut fram = faulty_fram();
+    S::prepare(&mut fram).await;
+    assert_eq!(S::contents(&mut fram).await.unwrap(), S::OLD);
+    fram
+}
+
+async fn sweep<S: Scenario>() {
+    let mut start = 0;
+    let mut fram = reset(prepared::<S>().await, |i2c| start = i2c.transactions());
+    S::update(&mut fram).await.unwrap();
+    let mut end = 0;
+    let mut fram = reset(fram, |i2c| end = i2c.transactions());
+    assert_eq!(S::contents(&mut fram).await.unwrap(), S::NEW);
+
+    for transaction in 0..end - start {
+        for fault in POWER_LOSS.into_iter().chain(bit_errors()) {
+            let fram = prepared::<S>().await;
+            let mut fram = reset(fram, |i2c| i2c.inject_after(transaction, fault));
+            let _ = S::update(&mut fram).await;
+
+            let mut fram = reset(fram, |i2c| i2c.clear());
+            let contents = S::contents(&mut fram).await;
+            if POWER_LOSS.contains(&fault) {
+                assert!(
+                    S::is_old_or_new(&contents),
+                    "{fault:?} at transaction {transaction}: {contents:?}"
+                );
+            }
+        }
+    }
+}
+
+const SLOTS: AbSlots = AbSlots::new(0x100, 0x140, 0x40);
+
+struct Slots;
+
+impl Scenario for Slots {
+    const OLD: &[u8] = b"second";
+    const NEW: &[u8] = b"third!";
+
+    async fn prepare(fram: &mut FaultyFram) {
+        let mut slots = SLOTS;
+        slots.commit(fram, b"first").await.unwrap();
+        slots.commit(fram, Self::OLD).await.unwrap();
+    }
+
+    async fn update(fram: &mut FaultyFram) -> Result<()> {
+        let mut slots = SLOTS;
+        slots.commit(fram, Self::NEW).await
+    }
+
+    async fn contents(fram: &mut FaultyFram) -> Result<Vec<u8>> {
+        let mut slots = SLOTS;
+        let mut buf = [0; 0x40];
+        let len = slots.load(fram, &mut buf).await?;
+        Ok(buf[..len].to_vec())
+    }
+}
+
+struct Journaled;
+
+impl Journaled {
+    async fn mount(fram: &mut FaultyFram) -> Result<Journal> {
+        Ok(Journal::mount(fram, 0x1000, 0x80).await?.0)
+    }
+}
+
+impl Scenario for Journaled {
+    const OLD: &[u8] = b"oldOLD";
+    const NEW: &[u8] = b"newNEW";
+
+    async fn prepare(fram: &mut FaultyFram) {
+        fram.write(0x100, &Self::OLD[..3]).await.unwrap();
+        fram.write(0x300, &Self::OLD[3..]).await.unwrap();
+    }
+
+    async fn update(fram: &mut FaultyFram) -> Result<()> {
+        let journal = Self::mount(fram).await?;
+        let mut transaction = journal.begin(fram);
+        transaction.write(0x100, &Self::NEW[..3]).await?;
+        transaction.write(0x300, &Self::NEW[3..]).await?;
+        transaction.commit().await
+    }
+
+    async fn contents(fram: &mut FaultyFram) -> Result<Vec<u8>> {
+        Self::mount(fram).await?;
+        let mut contents = [0; 6];
+        fram.read(0x100, &mut contents[..3]).await?;
+        fram.read(0x300, &mut contents[3..]).await?;
+        Ok(contents.to_vec())
+    }
+}
+
+struct Ring;
+
+impl Ring {
+    async fn mount(fram: &mut FaultyFram) -> Result<RingLog> {
+        RingLog::mount(fram, 0x400, fm24v10::RING_LOG_STATE_BYTES + 64).await
+    }
+}
+
+impl Scenario for Ring {
+    // Four entries of 16 bytes fill the data area, appending drops the oldest.
+    const OLD: &[u8] = b"0000000000111111111122222222223333333333";
+    const NEW: &[u8] = b"1111111111222222222233333333334444444444";
+
+    async fn prepare(fram: &mut FaultyFram) {
+        let mut log = Self::mount(fram).await.unwrap();
+        for entry in Self::OLD.chunks(10) {
+            log.append(fram, entry).await.unwrap();
+        }
+    }
+
+    async fn update(fram: &mut FaultyFram) -> Result<()> {
+        let mut log = Self::mount(fram).await?;
+        log.append(fram, &Self::NEW[30..]).await
+    }
+
+    async fn contents(fram: &mut FaultyFram) -> Result<Vec<u8>> {
+        let log = Self::mount(fram).await?;
+        let mut contents = Vec::new();
+        let mut iter = log.iter();
+        let mut buf = [0; 64];
+        while let Some(len) = iter.next(fram, &mut buf).await? {
+            contents.extend_from_slice(&buf[..len]);
+        }
+        Ok(contents)
+    }
+
+    /// Room for the new entry is made and persisted first, so the oldest
+    /// entry may be gone without the new one having been appended.
+    fn is_old_or_new(contents: &Result<Vec<u8>>) -> bool {
+        matches!(contents, Ok(c) if c == Self::OLD || c == Self::NEW || c == &Self::OLD[10..])
+    }
+}
+
+const STORE: KvStore = KvStore::new(0x800, 4 * 32, 32);
+
+struct Kv;
+
+impl Scenario for Kv {
+    const OLD: &[u8] = b"old";
+    const NEW: &[u8] = b"new";
+
+    async fn prepare(fram: &mut FaultyFram) {
+        STORE.format(fram).await.unwrap();
+        STORE.set(fram, "other", b"x").await.unwrap();
+        STORE.set(fram, "key", Self::OLD).await.unwrap();
+    }
+
+    async fn update(fram: &mut FaultyFram) -> Result<()> {
+        STORE.set(fram, "key", Self::NEW).await
+    }
+
+    async fn contents(fram: &mut FaultyFram) -> Result<Vec<u8>> {
+        let mut buf = [0; 32];
+        let len = STORE.get(fram, "key", &mut buf).await?.unwrap();
+        Ok(buf[..len].to_vec())
+    }
+
+    /// Values are updated in place, an interrupted update is detected.
+    fn is_old_or_new(contents: &Result<Vec<u8>>) -> bool {
+        match contents {
+            Ok(c) => c == Self::OLD || c == Self::NEW,
+            Err(e) => matches!(e, Error::RecordCorrupt),
+        }
+    }
+}
+
+#[tokio::test]
+async fn slots_survive_power_loss() {
+    sweep::<Slots>().await;
+}
+
+#[tokio::test]
+async fn journal_survives_power_loss() {
+    sweep::<Journaled>().await;
+}
+
+#[tokio::test]
+async fn ring_log_survives_power_loss() {
+    sweep::<Ring>().await;
+}
+
+#[tokio::test]
+async fn kv_store_survives_power_loss() {
+    sweep::<Kv>().await;
+}
@@ -0,0 +1,109 @@
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+use fm24v10::{Address, Error, Fm24v10};
+
+const NAK: ErrorKind = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+
+#[tokio::test]
+async fn continued_reads_use_the_address_latch() {
+    let expectations = [
+        Transaction::write_read(0x50, vec![0x00, 0x10], vec![1, 2]),
+        Transaction::read(0x50, vec![3, 4]),
+        Transaction::read(0x50, vec![5]),
+        // Seeking sends the memory address again.
+        Transaction::write_read(0x50, vec![0x01, 0x00], vec![6]),
+        Transaction::read(0x50, vec![7]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+    let mut reader = fram.reader(0x10);
+
+    let mut bytes = [0; 2];
+    reader.read(&mut bytes).await.unwrap();
+    assert_eq!(bytes, [1, 2]);
+    reader.read(&mut bytes).await.unwrap();
+    assert_eq!(bytes, [3, 4]);
+    reader.read(&mut bytes[..1]).await.unwrap();
+    assert_eq!(reader.position(), 0x15);
+
+    reader.seek(0x100);
+    reader.read(&mut bytes[..1]).await.unwrap();
+    assert_eq!(bytes[0], 6);
+    reader.read(&mut bytes[..1]).await.unwrap();
+    assert_eq!(bytes[0], 7);
+    i2c.done();
+}
+
+#[tokio::test]
+async fn failed_read_invalidates_the_latch() {
+    let expectations = [
+        Transaction::write_read(0x50, vec![0x00, 0x10], vec![1]),
+        Transaction::read(0x50, vec![0]).with_error(ErrorKind::Bus),
+        Transaction::write_read(0x50, vec![0x00, 0x11], vec![2]),
+        Transaction::read(0x50, vec![3]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+    let mut reader = fram.reader(0x10);
+
+    let mut byte = [0];
+    reader.read(&mut byte).await.unwrap();
+    assert!(matches!(
+        reader.read(&mut byte).await,
+        Err(Error::I2c(ErrorKind::Bus))
+    ));
+    assert_eq!(reader.position(), 0x11);
+    reader.read(&mut byte).await.unwrap();
+    assert_eq!(byte, [2]);
+    reader.read(&mut byte).await.unwrap();
+    assert_eq!(byte, [3]);
+    i2c.done();
+}
+
+#[tokio::test]
+async fn reading_after_sleep_wakes_and_sends_the_address() {
+    let expectations = [
+        Transaction::write(0x7C, vec![0x50 << 1]),
+        Transaction::write(0x43, vec![]),
+        Transaction::write(0x50, vec![]).with_error(NAK),
+        Transaction::write(0x50, vec![]),
+        Transaction::write_read(0x50, vec![0x00, 0x10], vec![1]),
+        Transaction::read(0x50, vec![2]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+    fram.sleep().await.unwrap();
+    let mut reader = fram.reader(0x10);
+
+    let mut byte = [0];
+    reader.read(&mut byte).await.unwrap();
+    assert_eq!(byte, [1]);
+    reader.read(&mut byte).await.unwrap();
+    assert_eq!(byte, [2]);
+    i2c.done();
+}
+
+#[tokio::test]
+async fn latch_rolls_over_at_the_end_of_the_array() {
+    let expectations = [
+        Transaction::write_read(0x51, vec![0xFF, 0xFE], vec![1, 2]),
+        // The latch points at 0 now, no memory address is needed.
+        Transaction::read(0x50, vec![3]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default());
+    let mut reader = fram.reader(0x1_FFFE);
+
+    let mut bytes = [0; 2];
+    reader.read(&mut bytes).await.unwrap();
+    assert_eq!(reader.remaining(), 0);
+    assert!(matches!(
+        reader.read(&mut bytes[..1]).await,
+        Err(Error::OutOfBounds)
+    ));
+
+    reader.seek(0);
+    reader.read(&mut bytes[..1]).await.unwrap();
+    assert_eq!(bytes[0], 3);
+    i2c.done();
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::fram;
+use fm24v10::{Error, RECORD_HEADER_BYTES};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Settings {
+    volume: u8,
+    name: [u8; 4],
+    offsets: (i16, i32),
+}
+
+const SETTINGS: Settings = Settings {
+    volume: 7,
+    name: *b"atov",
+    offsets: (-3, 100_000),
+};
+
+#[tokio::test]
+async fn store_load_round_trip() {
+    let mut fram = fram();
+    let mut buf = [0; 32];
+
+    let len = fram.store(0x40, &SETTINGS, &mut buf).await.unwrap();
+    assert!(len > RECORD_HEADER_BYTES);
+    let loaded: Settings = fram.load(0x40, &mut buf).await.unwrap();
+    assert_eq!(loaded, SETTINGS);
+}
+
+#[tokio::test]
+async fn load_without_record() {
+    let mut fram = fram();
+    let mut buf = [0; 32];
+
+    assert!(matches!(
+        fram.load::<Settings>(0, &mut buf).await,
+        Err(Error::RecordMissing)
+    ));
+}
+
+#[tokio::test]
+async fn load_detects_corrupt_payload() {
+    let mut fram = fram();
+    let mut buf = [0; 32];
+    fram.store(0, &SETTINGS, &mut buf).await.unwrap();
+
+    let mut byte = [0];
+    fram.read(RECORD_HEADER_BYTES as u32, &mut byte)
+        .await
+        .unwrap();
+    fram.write(RECORD_HEADER_BYTES as u32, &[byte[0] ^ 0x01])
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        fram.load::<Settings>(0, &mut buf).await,
+        Err(Error::RecordCorrupt)
+    ));
+}
+
+#[tokio::test]
+async fn load_detects_corrupt_length() {
+    let mut fram = fram();
+    let mut buf = [0; 32];
+    fram.store(0x1_FF00, &SETTINGS, &mut buf).await.unwrap();
+
+    // A length reaching past the end of the device.
+    fram.write(0x1_FF01, &0xFFFFu16.to_le_bytes())
+        .await
+        .unwrap();
+    assert!(matches!(
+        fram.load::<Settings>(0x1_FF00, &mut buf).await,
+        Err(Error::RecordCorrupt)
+    ));
+
+    // A length that fits the device but not the stored checksum.
+    fram.write(0x1_FF01, &20u16.to_le_bytes()).await.unwrap();
+    assert!(matches!(
+        fram.load::<Settings>(0x1_FF00, &mut buf).await,
+        Err(Error::RecordCorrupt)
+    ));
+}
+
+#[tokio::test]
+async fn small_buffers_are_not_reported_as_corruption() {
+    let mut fram = fram();
+    let mut buf = [0; 32];
+    let len = fram.store(0, &SETTINGS, &mut buf).await.unwrap();
+
+    let mut small = [0; 4];
+    assert!(matches!(
+        fram.load::<Settings>(0, &mut small).await,
+        Err(Error::Serialization)
+    ));
+    assert!(matches!(
+        fram.store(0, &SETTINGS, &mut buf[..len - 1]).await,
+        Err(Error::Serialization)
+    ));
+}
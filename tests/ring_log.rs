@@ -0,0 +1,167 @@
+mod common;
+
+use common::fram;
+use embedded_hal::i2c::ErrorKind;
+use fm24v10::sim::SimulatedFm24v10;
+use fm24v10::{
+    AbSlots, Error, Fm24v10, RING_LOG_ENTRY_HEADER_BYTES, RING_LOG_STATE_BYTES, RingLog,
+    SLOT_HEADER_BYTES,
+};
+
+const LOG: u32 = 0x400;
+const LOG_BYTES: usize = RING_LOG_STATE_BYTES + 64;
+const DATA: u32 = LOG + RING_LOG_STATE_BYTES as u32;
+
+async fn mount(fram: &mut Fm24v10<SimulatedFm24v10>) -> Result<RingLog, Error<ErrorKind>> {
+    RingLog::mount(fram, LOG, LOG_BYTES).await
+}
+
+async fn entries(fram: &mut Fm24v10<SimulatedFm24v10>, log: &RingLog) -> Vec<Vec<u8>> {
+    let mut entries = Vec::new();
+    let mut iter = log.iter();
+    let mut buf = [0; 64];
+    while let Some(len) = iter.next(fram, &mut buf).await.unwrap() {
+        entries.push(buf[..len].to_vec());
+    }
+    entries
+}
+
+#[tokio::test]
+async fn append_and_iterate() {
+    let mut fram = fram();
+    let mut log = mount(&mut fram).await.unwrap();
+    assert!(log.is_empty());
+
+    log.append(&mut fram, b"one").await.unwrap();
+    log.append(&mut fram, b"two").await.unwrap();
+
+    assert_eq!(log.len(), 2);
+    assert_eq!(entries(&mut fram, &log).await, [b"one", b"two"]);
+}
+
+#[tokio::test]
+async fn entries_survive_a_remount() {
+    let mut fram = fram();
+    let mut log = mount(&mut fram).await.unwrap();
+    log.append(&mut fram, b"one").await.unwrap();
+
+    let log = mount(&mut fram).await.unwrap();
+    assert_eq!(entries(&mut fram, &log).await, [b"one"]);
+}
+
+#[tokio::test]
+async fn full_log_drops_the_oldest_entries() {
+    let mut fram = fram();
+    let mut log = mount(&mut fram).await.unwrap();
+
+    // 16 bytes per entry, 4 fit into the data area.
+    for i in 0..10u8 {
+        log.append(&mut fram, &[i; 10]).await.unwrap();
+    }
+
+    assert_eq!(log.len(), 4);
+    let expected: Vec<Vec<u8>> = (6..10).map(|i| vec![i; 10]).collect();
+    assert_eq!(entries(&mut fram, &log).await, expected);
+}
+
+#[tokio::test]
+async fn entries_wrap_around() {
+    let mut fram = fram();
+    let mut log = mount(&mut fram).await.unwrap();
+
+    for i in 0..5u8 {
+        log.append(&mut fram, &[i; 13]).await.unwrap();
+    }
+
+    let expected: Vec<Vec<u8>> = (2..5).map(|i| vec![i; 13]).collect();
+    assert_eq!(entries(&mut fram, &log).await, expected);
+}
+
+#[tokio::test]
+async fn clear_removes_all_entries() {
+    let mut fram = fram();
+    let mut log = mount(&mut fram).await.unwrap();
+    log.append(&mut fram, b"one").await.unwrap();
+
+    log.clear(&mut fram).await.unwrap();
+
+    assert!(log.is_empty());
+    assert!(mount(&mut fram).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn oversized_entry_is_rejected() {
+    let mut fram = fram();
+    let mut log = mount(&mut fram).await.unwrap();
+
+    let data = [0; 64 - RING_LOG_ENTRY_HEADER_BYTES + 1];
+    assert!(matches!(
+        log.append(&mut fram, &data).await,
+        Err(Error::OutOfBounds)
+    ));
+}
+
+#[tokio::test]
+async fn small_buffer_is_a_serialization_error() {
+    let mut fram = fram();
+    let mut log = mount(&mut fram).await.unwrap();
+    log.append(&mut fram, b"entry").await.unwrap();
+
+    let mut buf = [0; 4];
+    assert!(matches!(
+        log.iter().next(&mut fram, &mut buf).await,
+        Err(Error::Serialization)
+    ));
+}
+
+#[tokio::test]
+async fn corrupt_payload_fails_iteration() {
+    let mut fram = fram();
+    let mut log = mount(&mut fram).await.unwrap();
+    log.append(&mut fram, b"one").await.unwrap();
+
+    fram.write(DATA + RING_LOG_ENTRY_HEADER_BYTES as u32, b"x")
+        .await
+        .unwrap();
+
+    let mut buf = [0; 8];
+    assert!(matches!(
+        log.iter().next(&mut fram, &mut buf).await,
+        Err(Error::RecordCorrupt)
+    ));
+}
+
+#[tokio::test]
+async fn corrupt_entry_length_fails_append() {
+    let mut fram = fram();
+    let mut log = mount(&mut fram).await.unwrap();
+    for i in 0..4u8 {
+        log.append(&mut fram, &[i; 10]).await.unwrap();
+    }
+
+    // The oldest entry claims to be larger than the whole log.
+    fram.write(DATA, &[0xFF, 0xFF]).await.unwrap();
+
+    assert!(matches!(
+        log.append(&mut fram, &[4; 10]).await,
+        Err(Error::RecordCorrupt)
+    ));
+    let log = mount(&mut fram).await.unwrap();
+    assert_eq!(log.len(), 4);
+}
+
+#[tokio::test]
+async fn inconsistent_state_is_rejected() {
+    let mut fram = fram();
+    mount(&mut fram).await.unwrap();
+
+    // No entries, but bytes in use.
+    let slot_bytes = RING_LOG_STATE_BYTES / 2;
+    let mut slots = AbSlots::new(LOG, LOG + slot_bytes as u32, slot_bytes);
+    let mut state = [0; 12];
+    state[4] = 16;
+    assert_eq!(slot_bytes, SLOT_HEADER_BYTES + state.len());
+    slots.commit(&mut fram, &state).await.unwrap();
+
+    assert!(matches!(mount(&mut fram).await, Err(Error::RecordCorrupt)));
+}
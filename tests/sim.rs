@@ -0,0 +1,171 @@
+use embedded_hal::i2c::{ErrorKind, I2c, NoAcknowledgeSource};
+use fm24v10::sim::SimulatedFm24v10;
+use fm24v10::variant::Fm24cl16b;
+use fm24v10::{Address, DeviceId, Error, Fm24v10, MANUFACTURER_CYPRESS};
+
+const NAK_ADDRESS: ErrorKind = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+
+fn sim() -> SimulatedFm24v10 {
+    SimulatedFm24v10::new(Address::default())
+}
+
+#[test]
+fn page_bit_selects_upper_half() {
+    let mut sim = sim();
+
+    sim.write(0x50, &[0x00, 0x10, 0xAA]).unwrap();
+    sim.write(0x51, &[0x00, 0x10, 0xBB]).unwrap();
+
+    assert_eq!(sim.memory()[0x0_0010], 0xAA);
+    assert_eq!(sim.memory()[0x1_0010], 0xBB);
+}
+
+#[test]
+fn address_rolls_over_at_the_end() {
+    let mut sim = sim();
+
+    sim.write(0x51, &[0xFF, 0xFF, 1, 2]).unwrap();
+
+    assert_eq!(sim.memory()[0x1_FFFF], 1);
+    assert_eq!(sim.memory()[0], 2);
+}
+
+#[test]
+fn current_address_read_continues_at_the_latch() {
+    let mut sim = sim();
+    sim.memory_mut()[0x100..0x104].copy_from_slice(&[1, 2, 3, 4]);
+
+    let mut bytes = [0; 2];
+    sim.write_read(0x50, &[0x01, 0x00], &mut bytes).unwrap();
+    assert_eq!(bytes, [1, 2]);
+    sim.read(0x50, &mut bytes).unwrap();
+    assert_eq!(bytes, [3, 4]);
+
+    // A write leaves the latch after the last byte written.
+    sim.write(0x50, &[0x02, 0x00, 9]).unwrap();
+    sim.read(0x50, &mut bytes).unwrap();
+    assert_eq!(bytes, [0, 0]);
+    assert_eq!(sim.memory()[0x200], 9);
+}
+
+#[test]
+fn other_addresses_are_not_acknowledged() {
+    let mut sim = SimulatedFm24v10::new(Address {
+        a1: 1,
+        ..Default::default()
+    });
+
+    assert_eq!(sim.write(0x50, &[0, 0]), Err(NAK_ADDRESS));
+    sim.write(0x52, &[0, 0, 7]).unwrap();
+    sim.write(0x53, &[0, 0, 8]).unwrap();
+    assert_eq!(sim.memory()[0], 7);
+    assert_eq!(sim.memory()[0x1_0000], 8);
+}
+
+#[test]
+fn reserved_slave_id_returns_device_id() {
+    let mut sim = sim();
+
+    let mut id = [0; 3];
+    sim.write_read(0x7C, &[0xA0], &mut id).unwrap();
+
+    assert_eq!(
+        DeviceId::from_bytes(id),
+        DeviceId::new(MANUFACTURER_CYPRESS, 0x400)
+    );
+}
+
+#[test]
+fn reserved_slave_id_checks_the_device_address() {
+    let mut sim = sim();
+
+    let mut id = [0; 3];
+    assert_eq!(
+        sim.write_read(0x7C, &[0xA4], &mut id),
+        Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data))
+    );
+    assert_eq!(sim.read(0x7C, &mut id), Err(NAK_ADDRESS));
+}
+
+#[test]
+fn sleep_command() {
+    let mut sim = sim();
+
+    // The sleep command is only accepted right after selecting the device.
+    assert_eq!(sim.write(0x43, &[]), Err(NAK_ADDRESS));
+    assert_eq!(
+        sim.write(0x7C, &[0xA4]),
+        Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data))
+    );
+    assert_eq!(sim.write(0x43, &[]), Err(NAK_ADDRESS));
+    sim.write(0x7C, &[0xA0]).unwrap();
+    sim.write(0x50, &[0, 0]).unwrap();
+    assert_eq!(sim.write(0x43, &[]), Err(NAK_ADDRESS));
+    assert!(!sim.is_sleeping());
+
+    sim.write(0x7C, &[0xA0]).unwrap();
+    sim.write(0x43, &[]).unwrap();
+    assert!(sim.is_sleeping());
+}
+
+#[test]
+fn addressing_wakes_from_sleep() {
+    let mut sim = sim();
+    sim.enter_sleep();
+
+    // The device isn't selected by other addresses and NAKs its own.
+    assert_eq!(sim.write(0x57, &[]), Err(NAK_ADDRESS));
+    assert!(sim.is_sleeping());
+    assert_eq!(sim.write(0x50, &[0, 0, 1]), Err(NAK_ADDRESS));
+    assert!(!sim.is_sleeping());
+    assert_eq!(sim.memory()[0], 0);
+
+    sim.write(0x50, &[0, 0, 1]).unwrap();
+    assert_eq!(sim.memory()[0], 1);
+}
+
+#[test]
+fn fm24cl16b_uses_one_address_byte() {
+    let mut sim = SimulatedFm24v10::new_variant(Fm24cl16b, Address::default());
+
+    sim.write(0x53, &[0x45, 0xAA]).unwrap();
+    assert_eq!(sim.memory()[0x345], 0xAA);
+
+    let mut byte = [0];
+    sim.write_read(0x53, &[0x45], &mut byte).unwrap();
+    assert_eq!(byte, [0xAA]);
+
+    // Address pins are ignored, the page select bits take their place.
+    assert_eq!(sim.write(0x58, &[0, 0]), Err(NAK_ADDRESS));
+}
+
+#[tokio::test]
+async fn fm24cl16b_driver_round_trip() {
+    let mut fram = Fm24v10::new_variant(
+        SimulatedFm24v10::new_variant(Fm24cl16b, Address::default()),
+        Fm24cl16b,
+        Address::default(),
+    );
+
+    fram.write(0x3FE, &[1, 2, 3]).await.unwrap();
+    let mut bytes = [0; 3];
+    fram.read(0x3FE, &mut bytes).await.unwrap();
+    assert_eq!(bytes, [1, 2, 3]);
+    assert!(matches!(
+        fram.write(0x7FF, &[1, 2]).await,
+        Err(Error::OutOfBounds)
+    ));
+
+    let sim = fram.release();
+    assert_eq!(&sim.memory()[0x3FE..0x401], &[1, 2, 3]);
+}
+
+#[tokio::test]
+async fn driver_writes_reach_the_released_sim() {
+    let mut fram = Fm24v10::new(sim(), Address::default());
+
+    fram.write(0x1_FFFE, &[1, 2]).await.unwrap();
+
+    let sim = fram.release();
+    assert_eq!(&sim.memory()[0x1_FFFE..], &[1, 2]);
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use common::fram;
+use crc::{CRC_32_ISO_HDLC, Crc};
+use fm24v10::sim::SimulatedFm24v10;
+use fm24v10::{AbSlots, Error, Fm24v10, SLOT_HEADER_BYTES};
+
+const SLOT_A: u32 = 0x100;
+const SLOT_B: u32 = 0x200;
+const SLOT_BYTES: usize = 64;
+const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+fn slots() -> AbSlots {
+    AbSlots::new(SLOT_A, SLOT_B, SLOT_BYTES)
+}
+
+/// Writes a valid slot with the given sequence number directly.
+async fn write_slot(fram: &mut Fm24v10<SimulatedFm24v10>, offset: u32, sequence: u32, data: &[u8]) {
+    let mut slot = Vec::new();
+    slot.extend_from_slice(&sequence.to_le_bytes());
+    slot.extend_from_slice(&(data.len() as u16).to_le_bytes());
+    slot.extend_from_slice(data);
+    let crc = CRC32.checksum(&slot).to_le_bytes();
+    slot.splice(6..6, crc);
+    fram.write(offset, &slot).await.unwrap();
+}
+
+async fn load(
+    fram: &mut Fm24v10<SimulatedFm24v10>,
+) -> Result<Vec<u8>, Error<embedded_hal::i2c::ErrorKind>> {
+    let mut buf = [0; SLOT_BYTES];
+    let len = slots().load(fram, &mut buf).await?;
+    Ok(buf[..len].to_vec())
+}
+
+#[tokio::test]
+async fn commit_load_alternates_slots() {
+    let mut fram = fram();
+    let mut slots = slots();
+
+    slots.commit(&mut fram, b"first").await.unwrap();
+    slots.commit(&mut fram, b"second").await.unwrap();
+    assert_eq!(load(&mut fram).await.unwrap(), b"second");
+
+    let mut payload = [0; 6];
+    fram.read(SLOT_B + SLOT_HEADER_BYTES as u32, &mut payload)
+        .await
+        .unwrap();
+    assert_eq!(&payload, b"second");
+}
+
+#[tokio::test]
+async fn blank_slots_are_missing() {
+    let mut fram = fram();
+
+    assert!(matches!(load(&mut fram).await, Err(Error::RecordMissing)));
+}
+
+#[tokio::test]
+async fn sequence_wraps_around() {
+    let mut fram = fram();
+    write_slot(&mut fram, SLOT_A, u32::MAX, b"old").await;
+    write_slot(&mut fram, SLOT_B, 0, b"new").await;
+
+    assert_eq!(load(&mut fram).await.unwrap(), b"new");
+
+    // The next commit replaces the older slot A.
+    slots().commit(&mut fram, b"newer").await.unwrap();
+    assert_eq!(load(&mut fram).await.unwrap(), b"newer");
+    let mut sequence = [0; 4];
+    fram.read(SLOT_A, &mut sequence).await.unwrap();
+    assert_eq!(u32::from_le_bytes(sequence), 1);
+}
+
+#[tokio::test]
+async fn sequence_wraps_around_in_slot_a() {
+    let mut fram = fram();
+    write_slot(&mut fram, SLOT_A, 0, b"new").await;
+    write_slot(&mut fram, SLOT_B, u32::MAX, b"old").await;
+
+    assert_eq!(load(&mut fram).await.unwrap(), b"new");
+}
+
+#[tokio::test]
+async fn torn_header_falls_back() {
+    let mut fram = fram();
+    let mut slots = slots();
+    slots.commit(&mut fram, b"first").await.unwrap();
+    slots.commit(&mut fram, b"second").await.unwrap();
+
+    // Only the sequence number of the new header made it to the chip.
+    fram.write(SLOT_B, &[9, 0, 0, 0]).await.unwrap();
+
+    assert_eq!(load(&mut fram).await.unwrap(), b"first");
+}
+
+#[tokio::test]
+async fn torn_length_falls_back() {
+    let mut fram = fram();
+    let mut slots = slots();
+    slots.commit(&mut fram, b"first").await.unwrap();
+    slots.commit(&mut fram, b"second").await.unwrap();
+
+    // A length beyond the slot isn't trusted.
+    fram.write(SLOT_B + 4, &[0xFF, 0xFF]).await.unwrap();
+
+    assert_eq!(load(&mut fram).await.unwrap(), b"first");
+}
+
+#[tokio::test]
+async fn both_slots_invalid() {
+    let mut fram = fram();
+    let mut slots = slots();
+    slots.commit(&mut fram, b"first").await.unwrap();
+    slots.commit(&mut fram, b"second").await.unwrap();
+
+    fram.write(SLOT_A + SLOT_HEADER_BYTES as u32, b"x")
+        .await
+        .unwrap();
+    fram.write(SLOT_B + 6, &[0; 4]).await.unwrap();
+
+    assert!(matches!(load(&mut fram).await, Err(Error::RecordMissing)));
+}
+
+#[tokio::test]
+async fn small_buffer_is_a_serialization_error() {
+    let mut fram = fram();
+    let mut slots = slots();
+    slots.commit(&mut fram, b"second").await.unwrap();
+
+    let mut buf = [0; 3];
+    assert!(matches!(
+        slots.load(&mut fram, &mut buf).await,
+        Err(Error::Serialization)
+    ));
+}
+
+#[tokio::test]
+async fn oversized_payload_is_rejected() {
+    let mut fram = fram();
+    let mut slots = slots();
+
+    let data = [0; SLOT_BYTES - SLOT_HEADER_BYTES + 1];
+    assert!(matches!(
+        slots.commit(&mut fram, &data).await,
+        Err(Error::OutOfBounds)
+    ));
+}
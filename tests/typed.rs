@@ -0,0 +1,87 @@
+mod common;
+
+use common::fram;
+use fm24v10::{Endian, Error};
+
+#[tokio::test]
+async fn scalars_in_both_byte_orders() {
+    let mut fram = fram();
+    fram.write_u32(0x10, 0x1122_3344, Endian::Big)
+        .await
+        .unwrap();
+    fram.write_u32(0x14, 0x1122_3344, Endian::Little)
+        .await
+        .unwrap();
+
+    let mut raw = [0; 8];
+    fram.read(0x10, &mut raw).await.unwrap();
+    assert_eq!(raw, [0x11, 0x22, 0x33, 0x44, 0x44, 0x33, 0x22, 0x11]);
+    assert_eq!(fram.read_u32(0x10, Endian::Big).await.unwrap(), 0x1122_3344);
+    assert_eq!(
+        fram.read_u32(0x14, Endian::Little).await.unwrap(),
+        0x1122_3344
+    );
+    assert_eq!(fram.read_u16(0x10, Endian::Little).await.unwrap(), 0x2211);
+}
+
+#[tokio::test]
+async fn every_scalar_type_round_trips() {
+    let mut fram = fram();
+    for endian in [Endian::Little, Endian::Big] {
+        fram.write_u8(0, 0xFE).await.unwrap();
+        fram.write_i8(1, -2).await.unwrap();
+        fram.write_u16(2, 0xBEEF, endian).await.unwrap();
+        fram.write_i16(4, -300, endian).await.unwrap();
+        fram.write_u64(8, u64::MAX - 1, endian).await.unwrap();
+        fram.write_i64(16, i64::MIN + 1, endian).await.unwrap();
+        fram.write_f32(24, 1.5, endian).await.unwrap();
+        fram.write_f64(32, -0.25, endian).await.unwrap();
+        fram.write_i32(40, -70_000, endian).await.unwrap();
+
+        assert_eq!(fram.read_u8(0).await.unwrap(), 0xFE);
+        assert_eq!(fram.read_i8(1).await.unwrap(), -2);
+        assert_eq!(fram.read_u16(2, endian).await.unwrap(), 0xBEEF);
+        assert_eq!(fram.read_i16(4, endian).await.unwrap(), -300);
+        assert_eq!(fram.read_u64(8, endian).await.unwrap(), u64::MAX - 1);
+        assert_eq!(fram.read_i64(16, endian).await.unwrap(), i64::MIN + 1);
+        assert_eq!(fram.read_f32(24, endian).await.unwrap(), 1.5);
+        assert_eq!(fram.read_f64(32, endian).await.unwrap(), -0.25);
+        assert_eq!(fram.read_i32(40, endian).await.unwrap(), -70_000);
+    }
+}
+
+#[tokio::test]
+async fn arrays_span_several_chunks() {
+    let mut fram = fram();
+    let values: Vec<u32> = (0..20).map(|i| 0x0102_0300 + i).collect();
+    fram.write_array(0x100, &values, Endian::Big).await.unwrap();
+
+    let mut raw = [0; 4];
+    fram.read(0x100 + 19 * 4, &mut raw).await.unwrap();
+    assert_eq!(raw, [0x01, 0x02, 0x03, 19]);
+
+    let mut read = [0u32; 20];
+    fram.read_array(0x100, &mut read, Endian::Big)
+        .await
+        .unwrap();
+    assert_eq!(read[..], values[..]);
+
+    let mut read = [0u16; 3];
+    fram.read_array(0x100, &mut read, Endian::Little)
+        .await
+        .unwrap();
+    assert_eq!(read, [0x0201, 0x0003, 0x0201]);
+}
+
+#[tokio::test]
+async fn out_of_bounds_array_writes_nothing() {
+    let mut fram = fram();
+
+    assert!(matches!(
+        fram.write_array(0x1_FFFC, &[1u32, 2], Endian::Little).await,
+        Err(Error::OutOfBounds)
+    ));
+    let mut raw = [0xFF; 4];
+    fram.read(0x1_FFFC, &mut raw).await.unwrap();
+    assert_eq!(raw, [0; 4]);
+}
@@ -0,0 +1,79 @@
+use embedded_hal_mock::eh1::digital::{Mock as PinMock, State, Transaction as PinTransaction};
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+use fm24v10::sim::SimulatedFm24v10;
+use fm24v10::sim::fault::{Fault, FaultyI2c};
+use fm24v10::{Address, Error, Fm24v10};
+
+fn write_expectations(data: Vec<u8>) -> [Transaction; 4] {
+    [
+        Transaction::transaction_start(0x50),
+        Transaction::write(0x50, vec![0x00, 0x10]),
+        Transaction::write(0x50, data),
+        Transaction::transaction_end(0x50),
+    ]
+}
+
+#[tokio::test]
+async fn pin_is_released_around_writes() {
+    let mut i2c = Mock::new(&write_expectations(vec![1, 2]));
+    let mut pin = PinMock::new(&[
+        PinTransaction::set(State::High),
+        PinTransaction::set(State::Low),
+        PinTransaction::set(State::High),
+    ]);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default())
+        .with_write_protect(pin.clone())
+        .unwrap();
+
+    fram.write(0x10, &[1, 2]).await.unwrap();
+    // Writes that don't reach the bus leave the pin alone.
+    fram.write(0x10, &[]).await.unwrap();
+    assert!(matches!(
+        fram.write(0x2_0000, &[1]).await,
+        Err(Error::OutOfBounds)
+    ));
+
+    i2c.done();
+    pin.done();
+}
+
+#[tokio::test]
+async fn pin_is_untouched_while_unlocked() {
+    let mut i2c = Mock::new(&[write_expectations(vec![1]), write_expectations(vec![2])].concat());
+    let mut pin = PinMock::new(&[
+        PinTransaction::set(State::High),
+        PinTransaction::set(State::Low),
+        PinTransaction::set(State::High),
+    ]);
+    let mut fram = Fm24v10::new(i2c.clone(), Address::default())
+        .with_write_protect(pin.clone())
+        .unwrap();
+
+    fram.unlock().unwrap();
+    fram.write(0x10, &[1]).await.unwrap();
+    fram.write(0x10, &[2]).await.unwrap();
+    fram.lock().unwrap();
+
+    i2c.done();
+    pin.done();
+}
+
+#[tokio::test]
+async fn pin_is_restored_after_failed_write() {
+    let mut i2c = FaultyI2c::new(SimulatedFm24v10::new(Address::default()));
+    i2c.inject(0, Fault::Nak);
+    let mut pin = PinMock::new(&[
+        PinTransaction::set(State::High),
+        PinTransaction::set(State::Low),
+        PinTransaction::set(State::High),
+    ]);
+    let mut fram = Fm24v10::new(i2c, Address::default())
+        .with_write_protect(pin.clone())
+        .unwrap();
+
+    assert!(matches!(fram.write(0x10, &[1]).await, Err(Error::I2c(_))));
+
+    let (i2c, _) = fram.release_with_pin();
+    assert_eq!(i2c.inner().memory()[0x10], 0);
+    pin.done();
+}